@@ -0,0 +1,100 @@
+use bevy::math::{Rect, Vec2};
+use bevy::prelude::{
+    Commands,
+    Component,
+    Entity,
+    Fixed,
+    Query,
+    Res,
+    Sprite,
+    Time,
+    Transform,
+};
+
+use crate::constants::{CREATURE_FRAME_HEIGHT, CREATURE_FRAME_WIDTH, MOVE_TWEEN_DURATION};
+use crate::simulation::Simulation;
+
+#[derive(Component)]
+pub struct Tween {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+}
+
+#[derive(Component)]
+pub struct WalkCycle {
+    frames: u32,
+}
+
+impl Tween {
+
+    pub fn new(from: Vec2, to: Vec2) -> Tween {
+        Tween { from, to, elapsed: 0. }
+    }
+}
+
+impl WalkCycle {
+
+    pub fn new(frames: u32) -> WalkCycle {
+        WalkCycle { frames }
+    }
+}
+
+/*
+ * Smooths out the start and the end of a movement
+ *
+ * @param    f32   progress, from 0 to 1
+ *
+ * @returns  f32   eased progress, from 0 to 1
+ */
+pub fn ease_in_out(t: f32) -> f32 {
+    let t = t.clamp(0., 1.);
+    t * t * (3. - 2. * t)
+}
+
+/*
+ * Gets the area of a sprite sheet that holds the given frame
+ *
+ * @param    u32    frame number, starting from zero
+ *
+ * @returns  Rect   area of the frame, the frames being laid out left to right
+ */
+pub fn get_frame_rect(frame: u32) -> Rect {
+    let x = CREATURE_FRAME_WIDTH * frame as f32;
+    Rect::new(x, 0., x + CREATURE_FRAME_WIDTH, CREATURE_FRAME_HEIGHT)
+}
+
+pub fn tween_handler(mut commands: Commands,
+                     time: Res<Time>,
+                     fixed: Res<Time<Fixed>>,
+                     sim: Res<Simulation>,
+                     mut sprites: Query<(Entity, &mut Tween, &mut Transform, &mut Sprite, Option<&WalkCycle>)>) {
+
+    // in real-time mode the next turn may come along before the tween would have finished
+    let duration = match sim.is_running() {
+        true => MOVE_TWEEN_DURATION.min(fixed.timestep().as_secs_f32()),
+        false => MOVE_TWEEN_DURATION,
+    };
+
+    for (entity, mut tween, mut transform, mut sprite, walk) in sprites.iter_mut() {
+        tween.elapsed += time.delta_secs();
+
+        let progress = (tween.elapsed / duration).min(1.);
+        let position = tween.from.lerp(tween.to, ease_in_out(progress));
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        // step through the walk cycle, coming to rest on the first frame
+        if let Some(walk) = walk {
+            let frame = match progress < 1. {
+                true => (progress * walk.frames as f32) as u32 % walk.frames,
+                false => 0,
+            };
+            sprite.rect = Some(get_frame_rect(frame));
+        }
+
+        if progress >= 1. {
+            commands.entity(entity).remove::<Tween>();
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// command line options, such as: bevy-example --seed 42 --record session.replay
+// or, without a window: bevy-example --headless --biome snow --seed 42 --turns 5000
+pub struct Args {
+    pub seed: Option<u64>,
+    pub record: Option<String>,
+    pub replay: Option<String>,
+    pub headless: bool,
+    pub biome: String,
+    pub turns: u32,
+}
+
+impl Args {
+
+    /*
+     * Reads the options the game was started with
+     *
+     * @returns  Args   parsed options; anything unrecognized is ignored
+     */
+    pub fn parse() -> Args {
+        let mut args = Args {
+            seed: None,
+            record: None,
+            replay: None,
+            headless: false,
+            biome: "marsh".to_string(),
+            turns: 1000,
+        };
+        let mut iter = std::env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--seed" => args.seed = iter.next().and_then(|s| s.parse().ok()),
+                "--record" => args.record = iter.next(),
+                "--replay" => args.replay = iter.next(),
+                "--headless" => args.headless = true,
+                "--biome" => args.biome = iter.next().unwrap_or(args.biome),
+                "--turns" => args.turns = iter.next().and_then(|s| s.parse().ok()).unwrap_or(args.turns),
+                _ => eprintln!("Ignoring unknown argument: {}", arg),
+            }
+        }
+
+        args
+    }
+}
@@ -0,0 +1,98 @@
+use crate::species::Species;
+use crate::utils::{distance, random};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Behaviour {
+    Idle,
+    Wander,
+    Flee,
+    Hunt,
+    ReturnHome,
+}
+
+impl Behaviour {
+
+    /*
+     * Describes the behaviour for the inspector
+     *
+     * @returns  string   such as "Hunting"
+     */
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Behaviour::Idle => "Idle",
+            Behaviour::Wander => "Wandering",
+            Behaviour::Flee => "Fleeing",
+            Behaviour::Hunt => "Hunting",
+            Behaviour::ReturnHome => "Returning home",
+        }
+    }
+
+    /*
+     * Picks the next behaviour of a creature based on its surroundings
+     *
+     * @param    (u32,u32)               current location
+     * @param    (u32,u32)               home location
+     * @param    string                  creature type, such as "wolf_"
+     * @param    Species                 profile of the creature type
+     * @param    bool                    whether the creature wants to eat
+     * @param    (string,(u32,u32))[]    type and location of every other
+     *                                   creature within sight
+     *
+     * @returns  Behaviour               the new behaviour
+     *           Option<(u32,u32)>       location the behaviour is aimed at,
+     *                                   such as the prey being hunted
+     */
+    pub fn decide(location: (u32,u32),
+                  home: (u32,u32),
+                  species_type: &str,
+                  species: &Species,
+                  hungry: bool,
+                  others: &[(&str,(u32,u32))]) -> (Behaviour, Option<(u32,u32)>) {
+
+        let nearest = |types: &[String]| {
+            others.iter()
+                  .filter(|(t, _)| types.iter().any(|s| s == t))
+                  .min_by_key(|(_, xy)| distance(location, *xy))
+                  .map(|(_, xy)| *xy)
+        };
+
+        // staying alive comes first, then food, then staying close to home
+        if let Some(threat) = nearest(&species.fears) {
+            return (Behaviour::Flee, Some(threat));
+        }
+
+        if hungry {
+            if let Some(prey) = nearest(&species.hunts) {
+                return (Behaviour::Hunt, Some(prey));
+            }
+        }
+
+        if distance(location, home) > species.home_radius {
+            return (Behaviour::ReturnHome, Some(home));
+        }
+
+        // pack animals drift towards the middle of the pack
+        if species.pack {
+            let pack: Vec<(u32,u32)> = others.iter()
+                                             .filter(|(t, _)| *t == species_type)
+                                             .map(|(_, xy)| *xy)
+                                             .collect();
+
+            if !pack.is_empty() {
+                let count = pack.len() as u32;
+                let middle = (pack.iter().map(|xy| xy.0).sum::<u32>() / count,
+                              pack.iter().map(|xy| xy.1).sum::<u32>() / count);
+
+                if distance(location, middle) > 2 {
+                    return (Behaviour::Wander, Some(middle));
+                }
+            }
+        }
+
+        if random(0, 100) < species.idle_chance {
+            return (Behaviour::Idle, None);
+        }
+
+        (Behaviour::Wander, None)
+    }
+}
@@ -1,8 +1,13 @@
 use bevy::prelude::{
+    ButtonInput,
+    Camera as BevyCamera,
+    ClearColorConfig,
+    IsDefaultUiCamera,
     Commands,
     Component,
     Entity,
     EventReader,
+    MouseButton,
     Query,
     ResMut,
     Camera2d,
@@ -10,11 +15,21 @@ use bevy::prelude::{
     Vec3,
     With,
     OrthographicProjection,
+    Res,
     Resource,
+    Time,
+    Vec2,
+    Window,
 };
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
 use bevy::{
-    input::keyboard::KeyboardInput,
-    input::ButtonState::Released,
+    input::mouse::MouseScrollUnit,
+    input::mouse::MouseWheel,
+    input::keyboard::KeyCode::Equal,
+    input::keyboard::KeyCode::Minus,
+    input::keyboard::KeyCode::NumpadAdd,
+    input::keyboard::KeyCode::NumpadSubtract,
     input::keyboard::KeyCode::KeyW,
     input::keyboard::KeyCode::KeyS,
     input::keyboard::KeyCode::KeyA,
@@ -22,13 +37,71 @@ use bevy::{
     input::keyboard::KeyCode::ArrowUp,
     input::keyboard::KeyCode::ArrowDown,
     input::keyboard::KeyCode::ArrowRight,
+    input::keyboard::KeyCode,
     input::keyboard::KeyCode::ArrowLeft,
+    input::keyboard::KeyCode::ShiftLeft,
+    input::keyboard::KeyCode::ShiftRight,
 };
 
 static GFX_SCALE: f32 = 2.0;
-static PIXELS_TRANSLATED: f32 = 8.0;
 
+// zoom levels are whole numbers of screen pixels per world pixel, keeping the art crisp
+static ZOOM_MIN: f32 = 1.0;
+static ZOOM_MAX: f32 = 4.0;
+
+// how quickly the zoom catches up with the chosen level
+static ZOOM_SMOOTHING: f32 = 12.0;
+
+// wheel distance in pixels that counts as one notch, for touchpads and smooth wheels
+static ZOOM_WHEEL_PIXELS: f32 = 100.0;
+
+// pixels per second when scrolling starts, how quickly that builds up, and the top speed
+static SCROLL_SPEED_START: f32 = 120.0;
+static SCROLL_ACCELERATION: f32 = 480.0;
+static SCROLL_SPEED_MAX: f32 = 600.0;
+
+// how stiffly the camera is pulled along behind whatever it follows
+static FOLLOW_SMOOTHING: f32 = 6.0;
+
+// how far the target may stray from the middle of the view before the camera moves
+static FOLLOW_DEAD_ZONE: f32 = 48.0;
+
+// furthest the view is thrown by a shake at full trauma, and how fast trauma wears off per second
+static SHAKE_MAX_OFFSET: f32 = 8.0;
+static SHAKE_DECAY: f32 = 1.5;
+
+use crate::combat::get_trauma;
+use crate::context::{ContextAction, ContextMenu};
+use crate::constants::{CAMERA_BOUNDS_MARGIN, EDGE_PAN_MARGIN, EDGE_PAN_SPEED, UI_LAYER};
+use crate::cursor::Cursor;
 use crate::gamestate::{Status, Gamestate};
+use crate::level::Level;
+use crate::tile::TILE_SIZE;
+
+/*
+ * Converts a position within the window into world space
+ *
+ * @param    Vec2   position in logical window pixels, from the top left
+ * @param    Vec2   logical size of the window, which is the physical size
+ *                  divided by the scale factor
+ * @param    Vec2   world position shown in the middle of the window
+ * @param    f32    logical window pixels per world pixel
+ * @returns  Vec2   position in world space, with y pointing up
+ */
+pub fn viewport_to_world(position: Vec2, viewport: Vec2, center: Vec2, scale: f32) -> Vec2 {
+    let offset = Vec2::new(position.x - viewport.x / 2., viewport.y / 2. - position.y);
+    center + offset / scale
+}
+
+pub fn register_context_items(context_menu: &mut ContextMenu) {
+    context_menu.register("follow", "Follow with camera", |target| target.creature.is_some());
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Follow {
+    Player,
+    Creature(u32),
+}
 
 #[derive(Component)]
 pub struct CameraEntity;
@@ -41,12 +114,39 @@ pub struct Camera {
     twodee: Entity,
     screen_height: f32,
     screen_width: f32,
+    scroll_speed: f32,
+    zoom: f32,
+    scale: f32,
+    wheel: f32,
+    bounds: Option<(f32,f32,f32,f32)>,
+    drag: Option<(f32,f32)>,
+    panning: bool,
+    follow: Option<Follow>,
+    velocity: Vec2,
+    trauma: f32,
 }
 
 impl Camera {
 
     pub fn new(x: f32, y: f32, z: f32, screen_height: f32, screen_width: f32) -> Camera {
-        Camera { x, y, z, twodee: Entity::from_raw(0), screen_height, screen_width }
+        Camera {
+            x,
+            y,
+            z,
+            twodee: Entity::from_raw(0),
+            screen_height,
+            screen_width,
+            scroll_speed: SCROLL_SPEED_START,
+            zoom: GFX_SCALE,
+            scale: GFX_SCALE,
+            wheel: 0.,
+            bounds: None,
+            drag: None,
+            panning: false,
+            follow: Some(Follow::Player),
+            velocity: Vec2::ZERO,
+            trauma: 0.,
+        }
     }
 
     pub fn start(&mut self, commands: &mut Commands) {
@@ -58,6 +158,24 @@ impl Camera {
                 ..OrthographicProjection::default_2d()
             },
         )).insert(CameraEntity).id();
+
+        // the menu, dialog and cursor are drawn on top by a camera that never
+        // moves or zooms, so they are laid out around the middle of the screen
+        commands.spawn((
+            Camera2d,
+            BevyCamera {
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..Default::default()
+            },
+            Transform::from_translation(Vec3::new(0., 0., self.z)),
+            OrthographicProjection {
+                scale: 1.0/GFX_SCALE,
+                ..OrthographicProjection::default_2d()
+            },
+            RenderLayers::layer(UI_LAYER),
+            IsDefaultUiCamera,
+        ));
     }
 
     pub fn get_x(&self) -> f32 {
@@ -68,54 +186,423 @@ impl Camera {
         self.y
     }
 
-    pub fn screen_height(&self) -> f32 {
-        self.screen_height
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /*
+     * Makes the camera glide after the player or a creature
+     *
+     * @param    Option<Follow>   what to follow, or None to stay put
+     */
+    pub fn set_follow(&mut self, follow: Option<Follow>) {
+        if self.follow != follow {
+            self.follow = follow;
+            self.velocity = Vec2::ZERO;
+        }
+    }
+
+    /*
+     * Checks whether the camera is being moved by hand, with shift and the
+     * movement keys, by dragging or by resting the mouse near the edge
+     *
+     * @returns  bool
+     */
+    pub fn is_panning(&self) -> bool {
+        self.panning
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
     }
 
-    pub fn screen_width(&self) -> f32 {
-        self.screen_width
+    pub fn get_follow(&self) -> Option<Follow> {
+        self.follow
+    }
+
+    /*
+     * Shakes the view; small amounts barely register, whilst larger ones
+     * add up to a violent shake that settles down over a second or so.
+     * Fights go through shake_from, but anything else that should rattle
+     * the screen, such as an explosion, can call this directly
+     *
+     * @param    f32   trauma to add, the total being capped at 1.0
+     */
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.);
+    }
+
+    /*
+     * Shakes the view after a turn, going by how hard the fights near it were
+     *
+     * @param    (u32,u32,u32)[]   location and amount of each hit
+     */
+    pub fn shake_from(&mut self, damage_events: &[(u32,u32,u32)]) {
+        let trauma = get_trauma(damage_events, (self.get_x(), self.get_y()));
+        self.add_trauma(trauma);
+    }
+
+    /*
+     * Eases the camera towards a point, leaving it alone whilst the point
+     * stays within the dead zone in the middle of the view
+     *
+     * @param    Vec2   point to follow, in world space
+     * @param    f32    seconds since the last frame
+     */
+    fn follow_point(&mut self, target: Vec2, dt: f32) {
+        let position = Vec2::new(self.x, self.y);
+        let offset = target - position;
+        let goal = position + offset - offset.clamp(Vec2::splat(-FOLLOW_DEAD_ZONE), Vec2::splat(FOLLOW_DEAD_ZONE));
+
+        // critically damped spring, which settles as quickly as possible without overshooting
+        let distance = position - goal;
+        let decay = (-FOLLOW_SMOOTHING * dt).exp();
+        let change = (self.velocity + FOLLOW_SMOOTHING * distance) * dt;
+        self.velocity = (self.velocity - FOLLOW_SMOOTHING * change) * decay;
+
+        let next = goal + (distance + change) * decay;
+        self.x = next.x;
+        self.y = next.y;
+    }
+
+    /*
+     * Keeps the camera over a level of the given size
+     *
+     * @param    u32   width of the level, in tiles
+     * @param    u32   height of the level, in tiles
+     */
+    pub fn set_bounds(&mut self, width: u32, height: u32) {
+
+        // tiles are drawn centered on their position, so the edges are half a tile out
+        let half_tile = TILE_SIZE / 2.;
+        self.bounds = Some((
+            -half_tile - CAMERA_BOUNDS_MARGIN,
+            -half_tile - CAMERA_BOUNDS_MARGIN,
+            TILE_SIZE * width as f32 - half_tile + CAMERA_BOUNDS_MARGIN,
+            TILE_SIZE * height as f32 - half_tile + CAMERA_BOUNDS_MARGIN,
+        ));
+    }
+
+    /*
+     * Moves the camera back within the bounds of the level, centering it
+     * along any axis where the level is smaller than the view
+     */
+    pub fn clamp_to_bounds(&mut self) {
+        let (left, bottom, right, top) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let half_width = self.screen_width / 2. / self.scale;
+        let half_height = self.screen_height / 2. / self.scale;
+
+        self.x = match right - left > half_width * 2. {
+            true => self.x.clamp(left + half_width, right - half_width),
+            false => (left + right) / 2.,
+        };
+        self.y = match top - bottom > half_height * 2. {
+            true => self.y.clamp(bottom + half_height, top - half_height),
+            false => (bottom + top) / 2.,
+        };
+    }
+
+    /*
+     * Gets how much larger the world currently appears than at the default zoom
+     *
+     * @returns  f32   1.0 at the default zoom, 2.0 when zoomed in twice as far
+     */
+    pub fn get_magnification(&self) -> f32 {
+        self.scale / GFX_SCALE
+    }
+
+    /*
+     * Zooms in or out by whole steps, within the allowed range
+     *
+     * @param    f32   number of steps, negative to zoom out
+     */
+    pub fn zoom_by(&mut self, steps: f32) {
+        self.zoom = (self.zoom + steps).clamp(ZOOM_MIN, ZOOM_MAX);
+    }
+
+    /*
+     * Finds the spot in the level that is under a point in the window
+     *
+     * @param    f32          x position in logical window pixels
+     * @param    f32          y position in logical window pixels
+     * @returns  (f32,f32)    position in world space
+     */
+    pub fn screen_to_world(&self, x: f32, y: f32) -> (f32,f32) {
+        let world = viewport_to_world(Vec2::new(x, y),
+                                      Vec2::new(self.screen_width, self.screen_height),
+                                      Vec2::new(self.x, self.y),
+                                      self.scale);
+        (world.x, world.y)
+    }
+
+    /*
+     * Finds where a point in the window lands amongst the menu, dialog and
+     * cursor, which are laid out around the middle of the UI camera
+     *
+     * @param    f32          x position in logical window pixels
+     * @param    f32          y position in logical window pixels
+     * @returns  (f32,f32)    position in UI space
+     */
+    pub fn screen_to_ui(&self, x: f32, y: f32) -> (f32,f32) {
+        let ui = viewport_to_world(Vec2::new(x, y),
+                                   Vec2::new(self.screen_width, self.screen_height),
+                                   Vec2::ZERO,
+                                   GFX_SCALE);
+        (ui.x, ui.y)
+    }
+
+    /*
+     * Gets how far the UI camera can see either side of the middle of the window
+     *
+     * @returns  (f32,f32)   half the width and height of the window in UI space
+     */
+    pub fn get_ui_bounds(&self) -> (f32,f32) {
+        (self.screen_width / 2. / GFX_SCALE, self.screen_height / 2. / GFX_SCALE)
+    }
+
+    /*
+     * Keeps track of the logical size of the window, which changes when it
+     * is resized or the 4K mode scale factor is in effect
+     *
+     * @param    f32   width in logical pixels
+     * @param    f32   height in logical pixels
+     */
+    pub fn set_screen_size(&mut self, width: f32, height: f32) {
+        self.screen_width = width;
+        self.screen_height = height;
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn camera_event_handler(mut cam: ResMut<Camera>,
                             gamestate: ResMut<Gamestate>,
-                            mut event_reader: EventReader<KeyboardInput>,
-                            mut positions: Query<&mut Transform, With<CameraEntity>>) {
+                            keys: Res<ButtonInput<KeyCode>>,
+                            time: Res<Time>,
+                            cursor: Res<Cursor>,
+                            lvl: Res<Level>,
+                            buttons: Res<ButtonInput<MouseButton>>,
+                            mut wheel: EventReader<MouseWheel>,
+                            windows: Query<&Window, With<PrimaryWindow>>,
+                            mut actions: EventReader<ContextAction>,
+                            mut positions: Query<(&mut Transform, &mut OrthographicProjection), With<CameraEntity>>) {
+
+    let playing = gamestate.get_status() == Status::Playing;
 
-    for event in event_reader.read() {
+    if let Ok(window) = windows.get_single() {
+        cam.set_screen_size(window.width(), window.height());
+    }
 
-        // ignored released events for now
-        if event.state == Released {
+    for event in wheel.read() {
+        if !playing {
             continue;
+        }
+
+        // gather up the wheel movement, zooming one step per notch
+        cam.wheel += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / ZOOM_WHEEL_PIXELS,
+        };
+        let steps = cam.wheel.trunc();
+        cam.wheel -= steps;
+        cam.zoom_by(steps);
+    }
+
+    if playing && keys.any_just_pressed([Equal, NumpadAdd]) {
+        cam.zoom_by(1.);
+    }
+    if playing && keys.any_just_pressed([Minus, NumpadSubtract]) {
+        cam.zoom_by(-1.);
+    }
 
-        // ignore keyboard events whilst the menu or dialog is visible
-        } else if gamestate.get_status() != Status::Playing {
-            continue
+    if (cam.zoom - cam.scale).abs() < 0.001 {
+        cam.scale = cam.zoom;
+    } else {
+        let blend = 1. - (-ZOOM_SMOOTHING * time.delta_secs()).exp();
+        cam.scale += (cam.zoom - cam.scale) * blend;
+    }
+
+    let mut direction = Vec2::ZERO;
+
+    // ignore the keyboard whilst the menu or dialog is visible, and leave
+    // the movement keys to the player unless shift is held
+    if playing && keys.any_pressed([ShiftLeft, ShiftRight]) {
+        if keys.any_pressed([ArrowUp, KeyW]) {
+            direction.y += 1.;
+        }
+        if keys.any_pressed([ArrowDown, KeyS]) {
+            direction.y -= 1.;
         }
+        if keys.any_pressed([ArrowRight, KeyD]) {
+            direction.x += 1.;
+        }
+        if keys.any_pressed([ArrowLeft, KeyA]) {
+            direction.x -= 1.;
+        }
+    }
 
-        for mut transform in positions.iter_mut() {
-            match event.key_code {
-
-                // move camera
-                ArrowUp | KeyW => {
-                    cam.y += PIXELS_TRANSLATED;
-                    transform.translation.y = cam.y;
-                },
-                ArrowDown | KeyS => {
-                    cam.y -= PIXELS_TRANSLATED;
-                    transform.translation.y = cam.y;
-                },
-                ArrowRight | KeyD => {
-                    cam.x += PIXELS_TRANSLATED;
-                    transform.translation.x = cam.x;
-                },
-                ArrowLeft | KeyA => {
-                    cam.x -= PIXELS_TRANSLATED;
-                    transform.translation.x = cam.x;
-                },
-
-                _ => (),
+    // build up speed the longer the keys are held, starting over once they are let go
+    if direction == Vec2::ZERO {
+        cam.scroll_speed = SCROLL_SPEED_START;
+    } else {
+        // scroll at the same speed on screen, whatever the zoom
+        let step = cam.scroll_speed * time.delta_secs() / cam.get_magnification();
+
+        // normalized, so that diagonals aren't faster than straight lines
+        let offset = direction.normalize() * step;
+        cam.x += offset.x;
+        cam.y += offset.y;
+        cam.set_follow(None);
+
+        cam.scroll_speed = (cam.scroll_speed + SCROLL_ACCELERATION * time.delta_secs()).min(SCROLL_SPEED_MAX);
+    }
+
+    let screen = match playing {
+        true => cursor.get_screen_position(),
+        false => None,
+    };
+
+    // dragging with the middle button keeps the same spot of the level under the mouse
+    match (screen, buttons.pressed(MouseButton::Middle)) {
+        (Some((x, y)), true) => {
+            if let Some((last_x, last_y)) = cam.drag {
+                let (world_x, world_y) = cam.screen_to_world(x, y);
+                let (last_world_x, last_world_y) = cam.screen_to_world(last_x, last_y);
+                cam.x -= world_x - last_world_x;
+                cam.y -= world_y - last_world_y;
+                cam.set_follow(None);
             }
+            cam.drag = Some((x, y));
+        },
+        _ => cam.drag = None,
+    }
+
+    cam.panning = direction != Vec2::ZERO || cam.drag.is_some();
+
+    // nudge the camera along whilst the mouse rests near the edge of the window
+    if let (Some((x, y)), None) = (screen, cam.drag) {
+        let mut edge = Vec2::ZERO;
+
+        if x < EDGE_PAN_MARGIN {
+            edge.x -= 1.;
+        } else if x > cam.screen_width - EDGE_PAN_MARGIN {
+            edge.x += 1.;
+        }
+        if y < EDGE_PAN_MARGIN {
+            edge.y += 1.;
+        } else if y > cam.screen_height - EDGE_PAN_MARGIN {
+            edge.y -= 1.;
+        }
+
+        if edge != Vec2::ZERO {
+            let offset = edge.normalize() * EDGE_PAN_SPEED * time.delta_secs() / cam.get_magnification();
+            cam.x += offset.x;
+            cam.y += offset.y;
+            cam.set_follow(None);
+            cam.panning = true;
+        }
+    }
+
+    for event in actions.read() {
+        if let ("follow", Some(id)) = (event.action, event.target.creature) {
+            cam.set_follow(Some(Follow::Creature(id)));
         }
     }
+
+    // keep up with the target, unless the menu or dialog is being shown on top
+    let target = match cam.follow {
+        Some(Follow::Player) => Some(lvl.get_player_position()),
+        Some(Follow::Creature(id)) => lvl.get_creature_location(id),
+        None => None,
+    };
+    match target {
+        Some((x, y)) if playing => {
+            cam.follow_point(Vec2::new(TILE_SIZE * x as f32, TILE_SIZE * y as f32), time.delta_secs());
+        },
+
+        // the creature being followed is gone
+        None if cam.follow.is_some() => cam.set_follow(None),
+        _ => (),
+    }
+
+    cam.clamp_to_bounds();
+
+    // the shake only moves the view, so anything placed using get_x and get_y stays put
+    cam.trauma = (cam.trauma - SHAKE_DECAY * time.delta_secs()).max(0.);
+    let shake = match playing {
+        true => SHAKE_MAX_OFFSET * cam.trauma * cam.trauma,
+        false => 0.,
+    };
+    let t = time.elapsed_secs();
+
+    for (mut transform, mut projection) in positions.iter_mut() {
+        transform.translation.x = cam.x + shake * (t * 43.).sin() * (t * 17.).cos();
+        transform.translation.y = cam.y + shake * (t * 37.).cos() * (t * 23.).sin();
+        projection.scale = 1.0/cam.scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEWPORT: Vec2 = Vec2::new(1280., 720.);
+
+    #[test]
+    fn middle_of_the_window_is_the_camera_position() {
+        let world = viewport_to_world(VIEWPORT / 2., VIEWPORT, Vec2::ZERO, 1.);
+        assert_eq!(world, Vec2::ZERO);
+    }
+
+    #[test]
+    fn corners_at_1x() {
+        assert_eq!(viewport_to_world(Vec2::ZERO, VIEWPORT, Vec2::ZERO, 1.), Vec2::new(-640., 360.));
+        assert_eq!(viewport_to_world(VIEWPORT, VIEWPORT, Vec2::ZERO, 1.), Vec2::new(640., -360.));
+    }
+
+    #[test]
+    fn corners_at_2x() {
+        assert_eq!(viewport_to_world(Vec2::ZERO, VIEWPORT, Vec2::ZERO, GFX_SCALE), Vec2::new(-320., 180.));
+        assert_eq!(viewport_to_world(VIEWPORT, VIEWPORT, Vec2::ZERO, GFX_SCALE), Vec2::new(320., -180.));
+    }
+
+    #[test]
+    fn scale_factor_halves_the_logical_viewport() {
+
+        // 4K mode overrides the scale factor to 2, so a 1280x720 window is 640x360 logical pixels
+        let logical = VIEWPORT / 2.;
+        assert_eq!(viewport_to_world(logical / 2., logical, Vec2::ZERO, GFX_SCALE), Vec2::ZERO);
+        assert_eq!(viewport_to_world(Vec2::ZERO, logical, Vec2::ZERO, GFX_SCALE), Vec2::new(-160., 90.));
+    }
+
+    #[test]
+    fn zoomed_in() {
+        let position = VIEWPORT / 2. + Vec2::new(40., 40.);
+        assert_eq!(viewport_to_world(position, VIEWPORT, Vec2::ZERO, 4.), Vec2::new(10., -10.));
+    }
+
+    #[test]
+    fn panned() {
+        let center = Vec2::new(300., -120.);
+        let position = VIEWPORT / 2. + Vec2::new(-64., 32.);
+        assert_eq!(viewport_to_world(position, VIEWPORT, center, GFX_SCALE), Vec2::new(268., -136.));
+    }
+
+    #[test]
+    fn panned_and_zoomed_round_trip() {
+        let center = Vec2::new(-48., 512.);
+        let scale = 3.;
+        let world = Vec2::new(-20., 540.);
+
+        // back into window pixels by hand
+        let offset = (world - center) * scale;
+        let position = Vec2::new(VIEWPORT.x / 2. + offset.x, VIEWPORT.y / 2. - offset.y);
+
+        let result = viewport_to_world(position, VIEWPORT, center, scale);
+        assert!((result - world).length() < 0.001);
+    }
 }
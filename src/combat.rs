@@ -0,0 +1,112 @@
+use bevy::{math::Vec3, prelude::{
+    Alpha,
+    AssetServer,
+    Commands,
+    Component,
+    Entity,
+    Query,
+    Res,
+    Text2d,
+    TextColor,
+    TextFont,
+    Time,
+    Transform,
+}};
+
+use crate::constants::{
+    DAMAGE_COLOR,
+    DAMAGE_FONT_SIZE,
+    DAMAGE_LIFETIME,
+    DAMAGE_RISE_SPEED,
+    TEXT_DIALOG_SCALE,
+    Z_VALUE_DAMAGE,
+};
+use crate::species::Stats;
+use crate::tile::TILE_SIZE;
+use crate::utils::random;
+
+// how far from the middle of the view a hit can be and still shake the camera
+static SHAKE_RANGE: f32 = 256.0;
+
+// trauma added for every point of damage dealt
+static TRAUMA_PER_DAMAGE: f32 = 0.04;
+
+#[derive(Component)]
+pub struct FloatingText {
+    age: f32,
+}
+
+/*
+ * Rolls the damage of a single strike
+ *
+ * @param    Stats   stats of the attacker
+ * @param    Stats   stats of the defender
+ *
+ * @returns  u32     hit points taken away from the defender, at least one
+ */
+pub fn get_damage(attacker: &Stats, defender: &Stats) -> u32 {
+    let attack = attacker.attack + random(0, 3);
+    attack.saturating_sub(defender.defense).max(1)
+}
+
+/*
+ * Works out how much the camera should shake from the latest fights
+ *
+ * @param    (u32,u32,u32)[]   location and amount of each hit
+ * @param    (f32,f32)         middle of the view, in world space
+ *
+ * @returns  f32               trauma, where only hits near the view count
+ */
+pub fn get_trauma(damage_events: &[(u32,u32,u32)], center: (f32,f32)) -> f32 {
+    let near = |x: u32, y: u32| {
+        (TILE_SIZE * x as f32 - center.0).abs() <= SHAKE_RANGE
+        && (TILE_SIZE * y as f32 - center.1).abs() <= SHAKE_RANGE
+    };
+
+    damage_events.iter()
+                 .filter(|(x, y, _)| near(*x, *y))
+                 .map(|(_, _, amount)| *amount as f32 * TRAUMA_PER_DAMAGE)
+                 .sum()
+}
+
+/*
+ * Spawns a damage number that drifts upwards before disappearing
+ *
+ * @param    f32    x coordinate, in world space
+ * @param    f32    y coordinate, in world space
+ * @param    u32    amount of damage
+ */
+pub fn spawn_damage_number(commands: &mut Commands, asset_server: &Res<AssetServer>, x: f32, y: f32, amount: u32) {
+    commands.spawn((
+        Text2d::from(amount.to_string()),
+        TextFont {
+            font: asset_server.load("fonts/eight_bit.ttf"),
+            font_size: DAMAGE_FONT_SIZE,
+            ..Default::default()
+        },
+        TextColor(DAMAGE_COLOR),
+        Transform {
+            scale: Vec3::new(TEXT_DIALOG_SCALE,TEXT_DIALOG_SCALE,TEXT_DIALOG_SCALE),
+            translation: Vec3::new(x, y, Z_VALUE_DAMAGE),
+            ..Default::default()
+        },
+        FloatingText { age: 0. },
+    ));
+}
+
+pub fn floating_text_handler(mut commands: Commands,
+                             time: Res<Time>,
+                             mut texts: Query<(Entity, &mut FloatingText, &mut Transform, &mut TextColor)>) {
+
+    for (entity, mut text, mut transform, mut color) in texts.iter_mut() {
+        text.age += time.delta_secs();
+
+        if text.age >= DAMAGE_LIFETIME {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += DAMAGE_RISE_SPEED * time.delta_secs();
+        color.0 = DAMAGE_COLOR.with_alpha(1. - text.age / DAMAGE_LIFETIME);
+    }
+}
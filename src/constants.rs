@@ -1,7 +1,21 @@
 use bevy::prelude::Color;
 
+// how far past the edge of the level the camera is allowed to scroll
+pub const CAMERA_BOUNDS_MARGIN: f32 = 32.;
+
 pub const CAMERA_HIGHEST_LEVEL: f32 = 1.0;
 
+pub const CONTEXT_ITEM_HEIGHT: f32 = 14.;
+pub const CONTEXT_ITEM_WIDTH: f32 = 96.;
+
+pub const CREATURE_FRAME_HEIGHT: f32 = 64.;
+pub const CREATURE_FRAME_WIDTH: f32 = 32.;
+
+pub const DAMAGE_COLOR: Color = Color::srgb(0.9,0.15,0.15);
+pub const DAMAGE_FONT_SIZE: f32 = 24.;
+pub const DAMAGE_LIFETIME: f32 = 0.8;
+pub const DAMAGE_RISE_SPEED: f32 = 24.;
+
 pub const DIALOG_MAIN_TEXT_COLOR: Color = Color::BLACK;
 pub const DIALOG_CHOICE_COLOR: Color = Color::srgb(0.65,0.65,0.65);
 pub const DIALOG_CHOICE_HEIGHT: f32 = 12.;
@@ -9,16 +23,43 @@ pub const DIALOG_CHOICE_WIDTH: f32 = 400.;
 pub const DIALOG_HOVER_COLOR: Color = Color::srgb(0.75,0.75,0.75);
 pub const DIALOG_FONT_SIZE: f32 = 20.;
 
-pub const MOUSE_GFX_HEIGHT: f32 = 16.;
-pub const MOUSE_GFX_WIDTH: f32 = 28.;
+// distance from the window edge, in pixels, where the mouse starts panning the camera
+pub const EDGE_PAN_MARGIN: f32 = 16.;
+pub const EDGE_PAN_SPEED: f32 = 400.;
+
+pub const HUD_FONT_SIZE: f32 = 14.;
+
+pub const LEVEL_HEIGHT: u32 = 22;
+pub const LEVEL_WIDTH: u32 = 22;
+
+pub const MOVE_TWEEN_DURATION: f32 = 0.2;
+
+// most terrain-only paths the pathfinder remembers before forgetting the oldest
+pub const PATH_CACHE_SIZE: usize = 256;
+
+// seconds between writes of the replay file whilst recording, on top of the one on exit
+pub const REPLAY_FLUSH_INTERVAL: f32 = 30.;
+
+// distance in pixels the mouse must travel with the button held before a click becomes a box select
+pub const SELECTION_DRAG_THRESHOLD: f32 = 4.;
 
 pub const SCREEN_HEIGHT: f32 = 720.0;
 pub const SCREEN_WIDTH: f32 = 1280.0;
 
 pub const TEXT_DIALOG_SCALE: f32 = 0.5;
 
+// distance in pixels between the mouse and the top left of its tooltip
+pub const TOOLTIP_OFFSET: f32 = 16.;
+
+// render layer of the menu, dialog and cursor, which have a camera of their own
+pub const UI_LAYER: usize = 1;
+
 pub const Z_VALUE_CURSOR: f32 = 0.3;
+pub const Z_VALUE_DAMAGE: f32 = 0.05;
+pub const Z_VALUE_HIGHLIGHT: f32 = 0.03;
 pub const Z_VALUE_MENU_ELEMENTS: f32 = 0.2;
 pub const Z_VALUE_MENU: f32 = 0.1;
+pub const Z_VALUE_PLAYER: f32 = 0.02;
+pub const Z_VALUE_SELECTION: f32 = 0.005;
 pub const Z_VALUE_DECAL: f32 = 0.01;
 pub const Z_VALUE_TILE: f32 = 0.001;
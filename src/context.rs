@@ -0,0 +1,200 @@
+use bevy::prelude::{
+    AssetServer,
+    Color,
+    Commands,
+    Event,
+    Res,
+    Resource,
+};
+
+use crate::constants::{CONTEXT_ITEM_HEIGHT, CONTEXT_ITEM_WIDTH, DIALOG_FONT_SIZE, Z_VALUE_MENU, Z_VALUE_MENU_ELEMENTS};
+use crate::level::Level;
+use crate::selection::Selection;
+use crate::text::Text;
+use crate::ui::UI;
+
+// what was under the mouse when the context menu was opened
+#[derive(Copy, Clone)]
+pub struct ContextTarget {
+    pub cell: (u32,u32),
+    pub creature: Option<u32>,
+    pub decal: bool,
+    pub walkable: bool,
+    pub occupied: bool,
+    pub selected: usize,
+}
+
+impl ContextTarget {
+
+    /*
+     * Looks at what can be found in a grid cell
+     *
+     * @param    Level          the current level
+     * @param    Selection      the creatures currently selected
+     * @param    (u32,u32)      the cell that was clicked on
+     *
+     * @returns  ContextTarget
+     */
+    pub fn find(lvl: &Level, selection: &Selection, cell: (u32,u32)) -> ContextTarget {
+        let (x, y) = cell;
+        let creature = lvl.get_creature_at(x, y).map(|c| c.get_id());
+
+        ContextTarget {
+            cell,
+            creature,
+            decal: lvl.get_decal_at(x, y).is_some(),
+            walkable: lvl.is_walkable(x, y),
+            occupied: creature.is_some() || lvl.get_player_position() == cell,
+            selected: selection.get_selected().len(),
+        }
+    }
+}
+
+// sent once an item is picked, for whichever subsystem registered it to carry out
+#[derive(Event)]
+pub struct ContextAction {
+    pub action: &'static str,
+    pub target: ContextTarget,
+}
+
+struct ContextItem {
+    action: &'static str,
+    label: &'static str,
+    applies: fn(&ContextTarget) -> bool,
+}
+
+struct ContextEntry {
+    action: &'static str,
+    ui: UI,
+    text: Text,
+}
+
+#[derive(Resource)]
+pub struct ContextMenu {
+    items: Vec<ContextItem>,
+    entries: Vec<ContextEntry>,
+    target: Option<ContextTarget>,
+}
+
+impl ContextMenu {
+
+    pub fn new() -> ContextMenu {
+        ContextMenu { items: vec![], entries: vec![], target: None }
+    }
+
+    /*
+     * Adds an item to the menu, listed in the order items are registered
+     *
+     * @param    string   action sent with the ContextAction event, such as "follow"
+     * @param    string   text shown on the item
+     * @param    fn       whether the item should be offered for a target
+     */
+    pub fn register(&mut self, action: &'static str, label: &'static str, applies: fn(&ContextTarget) -> bool) {
+        self.items.push(ContextItem { action, label, applies });
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /*
+     * Shows the items that apply to a target, with the top left corner at
+     * the mouse unless that would run off the edge of the window
+     *
+     * @param    Commands
+     * @param    AssetServer
+     * @param    ContextTarget   what was clicked on
+     * @param    f32             x position of the mouse in UI space
+     * @param    f32             y position of the mouse in UI space
+     * @param    (f32,f32)       half the width and height of the window in UI space
+     */
+    pub fn open(&mut self,
+                commands: &mut Commands,
+                asset_server: &Res<AssetServer>,
+                target: ContextTarget,
+                x: f32,
+                y: f32,
+                bounds: (f32,f32)) {
+
+        self.close(commands);
+
+        let items: Vec<&ContextItem> = self.items.iter()
+                                                 .filter(|item| (item.applies)(&target))
+                                                 .collect();
+        if items.is_empty() {
+            return;
+        }
+
+        let height = CONTEXT_ITEM_HEIGHT * items.len() as f32;
+        let left = match x + CONTEXT_ITEM_WIDTH > bounds.0 {
+            true => x - CONTEXT_ITEM_WIDTH,
+            false => x,
+        };
+        let top = match y - height < -bounds.1 {
+            true => y + height,
+            false => y,
+        };
+
+        for (i, item) in items.iter().enumerate() {
+            let item_x = left + CONTEXT_ITEM_WIDTH / 2.;
+            let item_y = top - CONTEXT_ITEM_HEIGHT / 2. - CONTEXT_ITEM_HEIGHT * i as f32;
+
+            let mut ui = UI::new(item.action.to_string(),
+                                 "img/ui/context_item.png".to_string(),
+                                 "img/ui/context_item_hover.png".to_string(),
+                                 CONTEXT_ITEM_HEIGHT,
+                                 CONTEXT_ITEM_WIDTH);
+            ui.render(commands, asset_server, item_x, item_y, Z_VALUE_MENU);
+
+            let mut text = Text::new(DIALOG_FONT_SIZE, Color::WHITE, item.label, false);
+            text.render("fonts/eight_bit.ttf", commands, asset_server, item_x, item_y, Z_VALUE_MENU_ELEMENTS);
+
+            self.entries.push(ContextEntry { action: item.action, ui, text });
+        }
+
+        self.target = Some(target);
+    }
+
+    pub fn close(&mut self, commands: &mut Commands) {
+        for entry in self.entries.iter_mut() {
+            entry.ui.free(commands);
+            entry.text.free(commands);
+        }
+        self.entries.clear();
+        self.target = None;
+    }
+
+    pub fn hover_events(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>, mouse_x: f32, mouse_y: f32) {
+        for entry in self.entries.iter_mut() {
+            let x = entry.ui.get_x();
+            let y = entry.ui.get_y();
+            let z = entry.ui.get_z();
+            if entry.ui.mouse_is_hovering(mouse_x, mouse_y) {
+                entry.ui.render_hover(commands, asset_server, x, y, z);
+            } else {
+                entry.ui.render(commands, asset_server, x, y, z);
+            }
+        }
+    }
+
+    pub fn is_hovering(&self, mouse_x: f32, mouse_y: f32) -> bool {
+        self.entries.iter().any(|entry| entry.ui.mouse_is_hovering(mouse_x, mouse_y))
+    }
+
+    /*
+     * Finds the item under the mouse
+     *
+     * @param    f32                    x position of the mouse in UI space
+     * @param    f32                    y position of the mouse in UI space
+     *
+     * @returns  Option<ContextAction>  the action to carry out, or None if
+     *                                  the click missed the menu
+     */
+    pub fn click_events(&self, mouse_x: f32, mouse_y: f32) -> Option<ContextAction> {
+        let target = self.target?;
+
+        self.entries.iter()
+                    .find(|entry| entry.ui.mouse_is_hovering(mouse_x, mouse_y))
+                    .map(|entry| ContextAction { action: entry.action, target })
+    }
+}
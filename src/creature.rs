@@ -1,45 +1,173 @@
 use bevy::prelude::Entity;
 
+use crate::behaviour::Behaviour;
 use crate::utils::random;
 
+// offsets of the eight cells surrounding a creature
+pub const NEIGHBOURS: [(i32,i32); 8] = [
+    (-1,-1),(0,-1),(1,-1),
+    (-1, 0),       (1, 0),
+    (-1, 1),(0, 1),(1, 1)
+];
+
+#[derive(Clone)]
 pub struct Creature {
+    id: u32,
     x: u32,
     y: u32,
     pub img: String,
+    species: String,
+    behaviour: Behaviour,
+    home: (u32,u32),
+    hunger: u32,
+    hp: u32,
+    alive: bool,
+    energy: i32,
+    goal: Option<(u32,u32)>,
+    drawn: (u32,u32),
+    facing_left: bool,
     initialized: bool,
     entity: Entity,
 }
 
 impl Creature {
-    pub fn new(x: u32, y: u32, img: String) -> Creature {
+    pub fn new(x: u32, y: u32, species: String, img: String) -> Creature {
         Creature {
+            id: 0,
             x,
             y,
             img,
+            species,
+            behaviour: Behaviour::Wander,
+            home: (x, y),
+            hunger: 0,
+            hp: 1,
+            alive: true,
+            energy: 0,
+            goal: None,
+            drawn: (x, y),
+            facing_left: false,
             initialized: false,
             entity: Entity::from_raw(0),
         }
     }
 
-    pub fn next_turn(&mut self) -> (u32,u32) {
-        let neighbours = [
-            [self.x-1,self.y-1],[self.x,self.y-1],[self.x+1,self.y-1],
-            [self.x-1,self.y  ],                  [self.x+1,self.y  ],
-            [self.x-1,self.y+1],[self.x,self.y+1],[self.x+1,self.y+1]
-        ];
-
+    /*
+     * Picks a random neighbouring cell to move to
+     *
+     * @param    u32         width of the level
+     * @param    u32         height of the level
+     *
+     * @returns  (u32,u32)   new location, or the existing one if the
+     *                       chosen neighbour is off the map
+     */
+    pub fn next_turn(&mut self, width: u32, height: u32) -> (u32,u32) {
         let random_neighbour = random(0, 8) as usize;
-        let new_location = neighbours[random_neighbour];
 
-        let nx = new_location[0];
-        let ny = new_location[1];
+        match Creature::get_neighbour(self.x, self.y, NEIGHBOURS[random_neighbour], width, height) {
+            Some(location) => location,
+            None => (self.x, self.y),
+        }
+    }
+
+    /*
+     * Offsets a location in the given direction
+     *
+     * @param    u32                 x coordinate
+     * @param    u32                 y coordinate
+     * @param    (i32,i32)           direction, such as (-1,1)
+     * @param    u32                 width of the level
+     * @param    u32                 height of the level
+     *
+     * @returns  Option<(u32,u32)>   new location, or None if it falls
+     *                               outside of the level
+     */
+    pub fn get_neighbour(x: u32, y: u32, direction: (i32,i32), width: u32, height: u32) -> Option<(u32,u32)> {
+        let nx = x.checked_add_signed(direction.0)?;
+        let ny = y.checked_add_signed(direction.1)?;
 
-        // if overflow, return existing location
-        if nx == 4294967295 || ny == 4294967295 {
-            return (self.get_x(),self.get_y());
+        if nx >= width || ny >= height {
+            return None;
         }
 
-        (nx,ny)
+        Some((nx,ny))
+    }
+
+    pub fn get_species(&self) -> &str {
+        self.species.as_str()
+    }
+    pub fn set_species(&mut self, species: String) {
+        self.species = species;
+    }
+
+    pub fn get_behaviour(&self) -> Behaviour {
+        self.behaviour
+    }
+    pub fn set_behaviour(&mut self, behaviour: Behaviour) {
+        self.behaviour = behaviour;
+    }
+
+    pub fn get_home(&self) -> (u32,u32) {
+        self.home
+    }
+    pub fn set_home(&mut self, home: (u32,u32)) {
+        self.home = home;
+    }
+
+    pub fn get_hunger(&self) -> u32 {
+        self.hunger
+    }
+    pub fn set_hunger(&mut self, hunger: u32) {
+        self.hunger = hunger;
+    }
+
+    pub fn get_hp(&self) -> u32 {
+        self.hp
+    }
+    pub fn set_hp(&mut self, hp: u32) {
+        self.hp = hp;
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+    pub fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    pub fn get_energy(&self) -> i32 {
+        self.energy
+    }
+    pub fn set_energy(&mut self, energy: i32) {
+        self.energy = energy;
+    }
+
+    pub fn get_goal(&self) -> Option<(u32,u32)> {
+        self.goal
+    }
+    pub fn set_goal(&mut self, goal: Option<(u32,u32)>) {
+        self.goal = goal;
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+    pub fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    pub fn get_drawn(&self) -> (u32,u32) {
+        self.drawn
+    }
+    pub fn set_drawn(&mut self, drawn: (u32,u32)) {
+        self.drawn = drawn;
+    }
+
+    pub fn get_facing_left(&self) -> bool {
+        self.facing_left
+    }
+    pub fn set_facing_left(&mut self, facing_left: bool) {
+        self.facing_left = facing_left;
     }
 
     pub fn get_entity(&mut self) -> Entity {
@@ -60,10 +188,10 @@ impl Creature {
         self.initialized = initialized;
     }
 
-    pub fn get_x(&mut self) -> u32 {
+    pub fn get_x(&self) -> u32 {
         self.x
     }
-    pub fn get_y(&mut self) -> u32 {
+    pub fn get_y(&self) -> u32 {
         self.y
     }
 
@@ -74,9 +202,84 @@ impl Creature {
         self.y = y;
     }
 
-    pub fn get_creature_type_max(type_str: &str) -> u32 {
-        match type_str {
-            _ => 1,
+    pub fn get_creature_type_max(_type_str: &str) -> u32 {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{LEVEL_HEIGHT, LEVEL_WIDTH};
+
+    const MAX_X: u32 = LEVEL_WIDTH - 1;
+    const MAX_Y: u32 = LEVEL_HEIGHT - 1;
+
+    fn reachable(x: u32, y: u32) -> Vec<(u32,u32)> {
+        NEIGHBOURS.iter()
+                  .filter_map(|d| Creature::get_neighbour(x, y, *d, LEVEL_WIDTH, LEVEL_HEIGHT))
+                  .collect()
+    }
+
+    fn assert_stays_inside(x: u32, y: u32) {
+        let mut creature = Creature::new(x, y, String::new(), String::new());
+        for _ in 0..200 {
+            let (nx, ny) = creature.next_turn(LEVEL_WIDTH, LEVEL_HEIGHT);
+            assert!(nx <= MAX_X && ny <= MAX_Y, "({},{}) left the map", nx, ny);
+            assert!(nx.abs_diff(x) <= 1 && ny.abs_diff(y) <= 1);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn interior_has_all_neighbours() {
+        assert_eq!(reachable(10, 10).len(), 8);
+    }
+
+    #[test]
+    fn bottom_left_corner() {
+        assert_eq!(reachable(0, 0), vec![(1,0),(0,1),(1,1)]);
+        assert_stays_inside(0, 0);
+    }
+
+    #[test]
+    fn bottom_right_corner() {
+        assert_eq!(reachable(MAX_X, 0), vec![(MAX_X-1,0),(MAX_X-1,1),(MAX_X,1)]);
+        assert_stays_inside(MAX_X, 0);
+    }
+
+    #[test]
+    fn top_left_corner() {
+        assert_eq!(reachable(0, MAX_Y), vec![(0,MAX_Y-1),(1,MAX_Y-1),(1,MAX_Y)]);
+        assert_stays_inside(0, MAX_Y);
+    }
+
+    #[test]
+    fn top_right_corner() {
+        assert_eq!(reachable(MAX_X, MAX_Y), vec![(MAX_X-1,MAX_Y-1),(MAX_X,MAX_Y-1),(MAX_X-1,MAX_Y)]);
+        assert_stays_inside(MAX_X, MAX_Y);
+    }
+
+    #[test]
+    fn left_border() {
+        assert_eq!(reachable(0, 10), vec![(0,9),(1,9),(1,10),(0,11),(1,11)]);
+        assert_stays_inside(0, 10);
+    }
+
+    #[test]
+    fn right_border() {
+        assert_eq!(reachable(MAX_X, 10), vec![(MAX_X-1,9),(MAX_X,9),(MAX_X-1,10),(MAX_X-1,11),(MAX_X,11)]);
+        assert_stays_inside(MAX_X, 10);
+    }
+
+    #[test]
+    fn bottom_border() {
+        assert_eq!(reachable(10, 0), vec![(9,0),(11,0),(9,1),(10,1),(11,1)]);
+        assert_stays_inside(10, 0);
+    }
+
+    #[test]
+    fn top_border() {
+        assert_eq!(reachable(10, MAX_Y), vec![(9,MAX_Y-1),(10,MAX_Y-1),(11,MAX_Y-1),(9,MAX_Y),(11,MAX_Y)]);
+        assert_stays_inside(10, MAX_Y);
+    }
+}
@@ -1,49 +1,168 @@
+use std::collections::HashMap;
+
+use bevy::app::AppExit;
 use bevy::input::ButtonState;
 use bevy::input::mouse::MouseButtonInput;
+use bevy::render::view::RenderLayers;
+use bevy::sprite::Anchor;
 use bevy::prelude::{
     AssetServer,
     Commands,
     Component,
+    CursorLeft,
     CursorMoved,
     Entity,
     EventReader,
+    EventWriter,
     Query,
     Res,
     ResMut,
     Sprite,
     Transform,
+    Vec2,
+    Visibility,
     With,
     MouseButton, Resource,
 };
 
 use crate::camera::Camera;
+use crate::context::{ContextAction, ContextMenu, ContextTarget};
 use crate::dialog::Dialog;
 use crate::gamestate::{Gamestate, Status};
+use crate::level::Level;
 use crate::menu::Menu;
-use crate::constants::Z_VALUE_CURSOR;
-use crate::options::toggle_option;
+use crate::constants::{UI_LAYER, Z_VALUE_CURSOR};
+use crate::options::{get_options, toggle_option};
+use crate::selection::Selection;
 
 #[derive(Component)]
 pub struct CursorEntity;
 
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CursorState {
+    Pointer,
+    Hand,
+    Crosshair,
+    Move,
+    Forbidden,
+}
+
+impl CursorState {
+
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            CursorState::Pointer => "pointer",
+            CursorState::Hand => "hand",
+            CursorState::Crosshair => "crosshair",
+            CursorState::Move => "move",
+            CursorState::Forbidden => "forbidden",
+        }
+    }
+}
+
+struct CursorShape {
+    img: String,
+    anchor: Anchor,
+}
+
 #[derive(Resource)]
 pub struct Cursor {
-    img: String,
+    shapes: HashMap<CursorState, CursorShape>,
+    state: CursorState,
+    hide_when_panning: bool,
     initialized: bool,
     entity: Entity,
     x: f32,
     y: f32,
+    screen: Option<(f32,f32)>,
 }
 
 impl Cursor {
 
-    pub fn new(img: String) -> Cursor {
-        Cursor { img, initialized: false, entity: Entity::from_raw(0), x: 0., y: 0. }
+    pub fn new(hide_when_panning: bool) -> Cursor {
+        Cursor {
+            shapes: Cursor::load_shapes(),
+            state: CursorState::Pointer,
+            hide_when_panning,
+            initialized: false,
+            entity: Entity::from_raw(0),
+            x: 0.,
+            y: 0.,
+            screen: None,
+        }
+    }
+
+    /*
+     * Loads the image and hotspot of each cursor state from the cursors file
+     *
+     * @returns  HashMap<CursorState,CursorShape>   shapes keyed by state,
+     *                                              always including the pointer
+     */
+    fn load_shapes() -> HashMap<CursorState, CursorShape> {
+
+        let contents = include_str!("../assets/img/ui/cursors.json");
+
+        let parsed: serde_json::Value = serde_json::from_str(contents).expect("Unable to open the cursors file.");
+
+        let mut shapes = HashMap::new();
+
+        let states = [
+            CursorState::Pointer,
+            CursorState::Hand,
+            CursorState::Crosshair,
+            CursorState::Move,
+            CursorState::Forbidden,
+        ];
+
+        for state in states {
+            let entry = &parsed[state.get_key()];
+
+            let img = match entry["img"].as_str() {
+                Some(s) => s.to_string(),
+                _ => continue,
+            };
+
+            let width = entry["width"].as_f64().unwrap_or(1.).max(1.) as f32;
+            let height = entry["height"].as_f64().unwrap_or(1.).max(1.) as f32;
+            let hotspot_x = entry["hotspot"][0].as_f64().unwrap_or(0.) as f32;
+            let hotspot_y = entry["hotspot"][1].as_f64().unwrap_or(0.) as f32;
+
+            // anchors run from -0.5 to 0.5 with y pointing up, hotspots are pixels from the top left
+            let anchor = Anchor::Custom(Vec2::new(hotspot_x / width - 0.5, 0.5 - hotspot_y / height));
+
+            shapes.insert(state, CursorShape { img, anchor });
+        }
+
+        shapes.entry(CursorState::Pointer).or_insert(CursorShape {
+            img: "img/ui/mouse_gfx.png".to_string(),
+            anchor: Anchor::TopLeft,
+        });
+
+        shapes
+    }
+
+    /*
+     * Builds the sprite for the current state, falling back to the pointer
+     * for states that have no shape of their own
+     *
+     * @param    AssetServer
+     *
+     * @returns  Sprite
+     */
+    fn get_sprite(&self, asset_server: &Res<AssetServer>) -> Sprite {
+        let shape = self.shapes.get(&self.state)
+                               .or_else(|| self.shapes.get(&CursorState::Pointer))
+                               .expect("The pointer cursor is always loaded.");
+
+        Sprite {
+            anchor: shape.anchor,
+            ..Sprite::from_image(asset_server.load(&shape.img))
+        }
     }
 
     pub fn render(&mut self,
                   commands: &mut Commands,
-                  asset_server: &mut Res<AssetServer>) {
+                  asset_server: &Res<AssetServer>) {
 
         if self.initialized {
             commands.entity(self.entity).despawn();
@@ -51,22 +170,29 @@ impl Cursor {
         }
 
         self.entity = commands.spawn((
-            Sprite::from_image(asset_server.load(&self.img)),
+            self.get_sprite(asset_server),
             Transform::from_xyz(self.x, self.y, Z_VALUE_CURSOR),
+            Visibility::Hidden,
+            RenderLayers::layer(UI_LAYER),
         )).insert(CursorEntity).id();
 
         self.initialized = true;
     }
 
-    pub fn hide(&mut self, commands: &mut Commands) {
-        if self.initialized {
-            commands.entity(self.entity).despawn();
-            self.initialized = false;
-        }
+    /*
+     * Gets where the mouse is within the window
+     *
+     * @returns  Option<(f32,f32)>   position in window pixels from the top
+     *                               left, or None if it is outside the window
+     */
+    pub fn get_screen_position(&self) -> Option<(f32,f32)> {
+        self.screen
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn mouse_event_handler(mut cursor_moved: EventReader<CursorMoved>,
+                           mut cursor_left: EventReader<CursorLeft>,
                            mut cursor_clicked: EventReader<MouseButtonInput>,
                            mut commands: Commands,
                            asset_server: Res<AssetServer>,
@@ -75,34 +201,53 @@ pub fn mouse_event_handler(mut cursor_moved: EventReader<CursorMoved>,
                            mut gamestate: ResMut<Gamestate>,
                            mut menu: ResMut<Menu>,
                            mut dialog: ResMut<Dialog>,
-                           mut positions: Query<&mut Transform, With<CursorEntity>>) {
+                           mut context_menu: ResMut<ContextMenu>,
+                           mut actions: EventWriter<ContextAction>,
+                           mut exit: EventWriter<AppExit>,
+                           lvl: Res<Level>,
+                           selection: Res<Selection>) {
 
     for event in cursor_moved.read() {
-        for mut transform in positions.iter_mut() {
+        cursor.screen = Some((event.position.x, event.position.y));
 
-            // record the cursor's position on the screen
-            cursor.x = event.position.x + cam.get_x() - (cam.screen_width()/2.);
-            cursor.y = cam.get_y() - event.position.y + (cam.screen_height()/2.);
+        // record the cursor's position amongst the menu and dialog
+        (cursor.x, cursor.y) = cam.screen_to_ui(event.position.x, event.position.y);
 
-            // move the mouse graphic to the desired location
-            transform.translation.x = cursor.x;
-            transform.translation.y = cursor.y;
+        match gamestate.get_status() {
 
-            match gamestate.get_status() {
-
-                Status::MenuOpen => {
-                    menu.hover_events(&mut commands, &asset_server, cursor.x, cursor.y);
-                },
-                Status::DialogOpen => {
-                    dialog.hover_events(&mut commands, &asset_server, cursor.x, cursor.y);
-                },
-                Status::Playing => {
-                },
-            }
+            Status::MenuOpen => {
+                menu.hover_events(&mut commands, &asset_server, cursor.x, cursor.y);
+            },
+            Status::DialogOpen => {
+                dialog.hover_events(&mut commands, &asset_server, cursor.x, cursor.y);
+            },
+            Status::Playing => {
+                context_menu.hover_events(&mut commands, &asset_server, cursor.x, cursor.y);
+            },
         }
     }
 
+    if cursor_left.read().next().is_some() {
+        cursor.screen = None;
+    }
+
     for event in cursor_clicked.read() {
+
+        // offer whatever can be done with the cell under the mouse
+        if event.state == ButtonState::Pressed && event.button == MouseButton::Right && gamestate.get_status() == Status::Playing {
+            context_menu.close(&mut commands);
+
+            let cell = cursor.get_screen_position().and_then(|(x, y)| {
+                let (world_x, world_y) = cam.screen_to_world(x, y);
+                lvl.get_cell_at(world_x, world_y)
+            });
+
+            if let Some(cell) = cell {
+                let target = ContextTarget::find(&lvl, &selection, cell);
+                context_menu.open(&mut commands, &asset_server, target, cursor.x, cursor.y, cam.get_ui_bounds());
+            }
+        }
+
         if event.state == ButtonState::Pressed && event.button == MouseButton::Left {
 
             match gamestate.get_status() {
@@ -110,12 +255,15 @@ pub fn mouse_event_handler(mut cursor_moved: EventReader<CursorMoved>,
                 Status::MenuOpen => {
                     let response = menu.click_events(&mut commands,
                                                             &asset_server,
-                                                            &cam,
                                                             &mut gamestate,
                                                             cursor.x,
                                                             cursor.y);
 
                     match response.as_str() {
+                        "quit" => {
+                            exit.send(AppExit::Success);
+                            continue;
+                        },
                         "4k_mode" => {
                             toggle_option("4K Mode".to_string());
                         },
@@ -128,13 +276,20 @@ pub fn mouse_event_handler(mut cursor_moved: EventReader<CursorMoved>,
                         "fullscreen" => {
                             toggle_option("Fullscreen".to_string());
                         },
+                        // takes effect straight away, so there is nothing to restart for
+                        "hide_cursor" => {
+                            toggle_option("Hide cursor when panning".to_string());
+                            cursor.hide_when_panning = get_options().hide_cursor_when_panning;
+                            menu.render(&mut commands, &asset_server);
+                            continue;
+                        },
                         _ => {
                             continue;
                         }
                     };
 
                     menu.set_options_modified_flag();
-                    menu.render(&mut commands, &asset_server, &cam);
+                    menu.render(&mut commands, &asset_server);
                 },
 
                 Status::DialogOpen => {
@@ -146,9 +301,89 @@ pub fn mouse_event_handler(mut cursor_moved: EventReader<CursorMoved>,
                     }
                 },
 
+                // clicking anywhere closes the context menu, carrying out the item if one was hit
                 Status::Playing => {
+                    if let Some(action) = context_menu.click_events(cursor.x, cursor.y) {
+                        actions.send(action);
+                    }
+                    context_menu.close(&mut commands);
                 },
             }
         }
     }
 }
+
+// picks the cursor to match whatever the mouse is over
+#[allow(clippy::too_many_arguments)]
+pub fn cursor_state_handler(asset_server: Res<AssetServer>,
+                            gamestate: Res<Gamestate>,
+                            cam: Res<Camera>,
+                            lvl: Res<Level>,
+                            menu: Res<Menu>,
+                            dialog: Res<Dialog>,
+                            context_menu: Res<ContextMenu>,
+                            mut cursor: ResMut<Cursor>,
+                            mut sprites: Query<&mut Sprite, With<CursorEntity>>) {
+
+    let (x, y) = (cursor.x, cursor.y);
+
+    let state = match gamestate.get_status() {
+        _ if cam.is_dragging() => CursorState::Move,
+
+        Status::MenuOpen if menu.is_hovering(x, y) => CursorState::Hand,
+        Status::DialogOpen if dialog.is_hovering(x, y) => CursorState::Hand,
+
+        Status::Playing if context_menu.is_open() => match context_menu.is_hovering(x, y) {
+            true => CursorState::Hand,
+            false => CursorState::Pointer,
+        },
+
+        Status::Playing => {
+            let cell = cursor.get_screen_position().and_then(|(screen_x, screen_y)| {
+                let (world_x, world_y) = cam.screen_to_world(screen_x, screen_y);
+                lvl.get_cell_at(world_x, world_y)
+            });
+
+            match cell {
+                Some((x, y)) if lvl.get_creature_at(x, y).is_some() => CursorState::Crosshair,
+                Some((x, y)) if !lvl.is_walkable(x, y) => CursorState::Forbidden,
+                _ => CursorState::Pointer,
+            }
+        },
+
+        _ => CursorState::Pointer,
+    };
+
+    if cursor.state == state {
+        return;
+    }
+    cursor.state = state;
+
+    for mut sprite in sprites.iter_mut() {
+        *sprite = cursor.get_sprite(&asset_server);
+    }
+}
+
+// keeps the mouse graphic on the mouse every frame, as the window can change size or
+// scale without the mouse moving, and hides it whilst it is outside the window
+pub fn cursor_position_handler(cam: Res<Camera>,
+                               mut cursor: ResMut<Cursor>,
+                               mut positions: Query<(&mut Transform, &mut Visibility), With<CursorEntity>>) {
+
+    let screen = cursor.get_screen_position();
+    if let Some((x, y)) = screen {
+        (cursor.x, cursor.y) = cam.screen_to_ui(x, y);
+    }
+
+    let hidden = screen.is_none() || (cursor.hide_when_panning && cam.is_panning());
+
+    for (mut transform, mut visibility) in positions.iter_mut() {
+        transform.translation.x = cursor.x;
+        transform.translation.y = cursor.y;
+
+        *visibility = match hidden {
+            true => Visibility::Hidden,
+            false => Visibility::Visible,
+        };
+    }
+}
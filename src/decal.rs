@@ -1,19 +1,22 @@
 use bevy::prelude::Entity;
 
+#[derive(Clone)]
 pub struct Decal {
     x: u32,
     y: u32,
     pub img: String,
+    decal_type: String,
     initialized: bool,
     entity: Entity,
 }
 
 impl Decal {
-    pub fn new(x: u32, y: u32, img: String) -> Decal {
+    pub fn new(x: u32, y: u32, decal_type: String, img: String) -> Decal {
         Decal {
             x,
             y,
             img,
+            decal_type,
             initialized: false,
             entity: Entity::from_raw(0),
         }
@@ -33,14 +36,21 @@ impl Decal {
     pub fn set_image(&mut self, img: String) {
         self.img = img;
     }
+    pub fn get_decal_type(&self) -> &str {
+        self.decal_type.as_str()
+    }
+    pub fn set_decal_type(&mut self, decal_type: String) {
+        self.decal_type = decal_type;
+    }
+
     pub fn set_initialized(&mut self, initialized: bool) {
         self.initialized = initialized;
     }
 
-    pub fn get_x(&mut self) -> u32 {
+    pub fn get_x(&self) -> u32 {
         self.x
     }
-    pub fn get_y(&mut self) -> u32 {
+    pub fn get_y(&self) -> u32 {
         self.y
     }
 
@@ -60,4 +70,41 @@ impl Decal {
             _ => 1,
         }
     }
+
+    /*
+     * Whether or not creatures are able to walk over a given decal type
+     *
+     * @param    string   decal type, such as "rock_"
+     *
+     * @returns  bool     true if the decal blocks movement
+     */
+    pub fn get_decal_type_blocking(type_str: &str) -> bool {
+        matches!(type_str, "asteroid_" | "cactus_" | "planet_" | "rock_")
+    }
+
+    /*
+     * Additional movement cost of walking over a given decal type
+     *
+     * @param    string   decal type, such as "plant_"
+     *
+     * @returns  u32      number of extra turns needed to cross it
+     */
+    pub fn get_decal_type_movement_cost(type_str: &str) -> u32 {
+        match type_str {
+            "dead_vegetation_" => 1,
+            "plant_"           => 1,
+            _ => 0,
+        }
+    }
+
+    /*
+     * Whether or not plant eating creatures are able to graze on a decal type
+     *
+     * @param    string   decal type, such as "flower_"
+     *
+     * @returns  bool     true if the decal is edible
+     */
+    pub fn get_decal_type_edible(type_str: &str) -> bool {
+        matches!(type_str, "dead_vegetation_" | "flower_" | "leaves_" | "mushroom_" | "plant_")
+    }
 }
\ No newline at end of file
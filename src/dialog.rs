@@ -2,10 +2,9 @@ use bevy::prelude::{
     AssetServer,
     Commands,
     Res,
-    ResMut, Resource,
+    Resource,
 };
 
-use crate::camera::Camera;
 use crate::constants::{Z_VALUE_MENU, Z_VALUE_MENU_ELEMENTS, DIALOG_MAIN_TEXT_COLOR, DIALOG_CHOICE_COLOR, DIALOG_CHOICE_HEIGHT, DIALOG_FONT_SIZE};
 use crate::text::Text;
 use crate::ui::UI;
@@ -13,6 +12,7 @@ use crate::ui::UI;
 struct DialogChoice {
     text: Text,
     next: i64,
+    min_turn: u32,
 }
 
 #[derive(Resource)]
@@ -21,6 +21,7 @@ pub struct Dialog {
     ui: UI,
     text: Text,
     dialog_choices: Vec<DialogChoice>,
+    turn: u32,
 }
 
 impl Dialog {
@@ -37,7 +38,7 @@ impl Dialog {
 
         let text = Text::new(DIALOG_FONT_SIZE, DIALOG_MAIN_TEXT_COLOR, "", false);
 
-        Dialog {initialized: false, ui, text, dialog_choices: vec![]}
+        Dialog {initialized: false, ui, text, dialog_choices: vec![], turn: 0}
     }
 
     pub fn load_dialog(&mut self, commands: &mut Commands, number: i8) {
@@ -88,10 +89,14 @@ impl Dialog {
 
             let choice_next = dialog_entry["choices"][&choice_entry]["next"].as_i64().unwrap_or_default();
 
+            // choices can be held back until enough turns have gone by
+            let choice_min_turn = dialog_entry["choices"][&choice_entry]["min_turn"].as_u64().unwrap_or_default() as u32;
+
             self.dialog_choices.push(
                 DialogChoice {
                     text: Text::new(DIALOG_FONT_SIZE, DIALOG_CHOICE_COLOR, &choice_text, true),
                     next: choice_next,
+                    min_turn: choice_min_turn,
                 }
             );
 
@@ -99,14 +104,16 @@ impl Dialog {
         }
     }
 
-    pub fn render(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>, camera: &ResMut<Camera>) {
+    pub fn render(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>, turn: u32) {
 
         if self.initialized {
             self.free(commands);
         }
 
-        let x = camera.get_x();
-        let y = camera.get_y()-76.;
+        self.turn = turn;
+
+        let x = 0.;
+        let y = -76.;
 
         self.ui.render(commands, asset_server, x, y, Z_VALUE_MENU);
 
@@ -120,7 +127,7 @@ impl Dialog {
         // each line of text is 20 / 2 = 10px of space
         text_y -= (self.text.lines() as f32) * 10.;
 
-        for d in self.dialog_choices.iter_mut() {
+        for d in self.dialog_choices.iter_mut().filter(|d| d.min_turn <= turn) {
             d.text.render("fonts/eight_bit.ttf", commands, asset_server, text_x, text_y, Z_VALUE_MENU_ELEMENTS);
             text_x -= DIALOG_CHOICE_HEIGHT;
             text_y -= 14.;
@@ -155,7 +162,8 @@ impl Dialog {
             return;
         }
 
-        for d in self.dialog_choices.iter_mut() {
+        let turn = self.turn;
+        for d in self.dialog_choices.iter_mut().filter(|d| d.min_turn <= turn) {
             let was_hovering = d.text.get_hover();
             let is_hovering = d.text.mouse_is_hovering(mouse_x, mouse_y);
 
@@ -170,6 +178,12 @@ impl Dialog {
         }
     }
 
+    pub fn is_hovering(&self, mouse_x: f32, mouse_y: f32) -> bool {
+        self.visible() && self.dialog_choices.iter()
+                                             .filter(|d| d.min_turn <= self.turn)
+                                             .any(|d| d.text.mouse_is_hovering(mouse_x, mouse_y))
+    }
+
     pub fn click_events(&mut self,
                         mouse_x: f32,
                         mouse_y: f32) -> i64 {
@@ -178,7 +192,7 @@ impl Dialog {
             return 0;
         }
 
-        for d in self.dialog_choices.iter_mut() {
+        for d in self.dialog_choices.iter().filter(|d| d.min_turn <= self.turn) {
             if d.text.mouse_is_hovering(mouse_x, mouse_y) {
                 return d.next;
             }
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+// upper limit on the number of creatures, so that breeding can't flood the map
+pub const MAX_CREATURES: usize = 60;
+
+// how many turns of population statistics are kept around
+pub const POPULATION_HISTORY: usize = 1000;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Diet {
+    Meat,
+    Plants,
+    None,
+}
+
+impl Diet {
+
+    pub fn parse(diet: &str) -> Diet {
+        match diet {
+            "meat" => Diet::Meat,
+            "plants" => Diet::Plants,
+            _ => Diet::None,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PopulationStats {
+    pub counts: BTreeMap<String, u32>,
+    pub births: u32,
+    pub eaten: u32,
+    pub killed: u32,
+    pub starved: u32,
+    pub collisions: u32,
+}
+
+impl PopulationStats {
+
+    pub fn new() -> PopulationStats {
+        Default::default()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+}
+
+/*
+ * Converts a creature type into something presentable
+ *
+ * @param    string   creature type, such as "alien_creeper_"
+ *
+ * @returns  string   display name, such as "alien creeper"
+ */
+pub fn get_display_name(species_type: &str) -> String {
+    species_type.trim_end_matches('_').replace('_', " ")
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::{
+    AssetServer,
+    Commands,
+    EventReader,
+    Res,
+    ResMut,
+};
+
+use crate::context::{ContextAction, ContextMenu};
+use crate::level::Level;
+use crate::selection::Selection;
+
+pub fn register_context_items(context_menu: &mut ContextMenu) {
+    context_menu.register("remove_decal", "Remove decal", |target| target.decal);
+    context_menu.register("spawn_creature", "Spawn creature here", |target| target.walkable && !target.occupied);
+    context_menu.register("move_selected", "Move selected here", |target| target.selected > 0 && target.walkable);
+}
+
+// changes made to the level from the context menu
+pub fn editor_handler(mut commands: Commands,
+                      asset_server: Res<AssetServer>,
+                      selection: Res<Selection>,
+                      mut lvl: ResMut<Level>,
+                      mut actions: EventReader<ContextAction>) {
+
+    for event in actions.read() {
+        let (x, y) = event.target.cell;
+
+        let changed = match event.action {
+            "remove_decal" => lvl.remove_decal(x, y),
+            "spawn_creature" => lvl.spawn_creature(x, y).is_some(),
+            "move_selected" => {
+                lvl.send_creatures(selection.get_selected(), (x, y));
+                false
+            },
+            _ => false,
+        };
+
+        if changed {
+            lvl.render(&mut commands, &asset_server);
+        }
+    }
+}
@@ -5,9 +5,9 @@ use bevy::prelude::{
     ResMut, Resource, 
 };
 
-use crate::camera::Camera;
 use crate::dialog::Dialog;
 use crate::menu::Menu;
+use crate::scheduler::TurnCounter;
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum Status {
@@ -44,10 +44,10 @@ impl Gamestate {
 pub fn gamestate_handler(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    cam: ResMut<Camera>,
     mut gamestate: ResMut<Gamestate>,
     mut menu: ResMut<Menu>,
-    mut dialog: ResMut<Dialog>
+    mut dialog: ResMut<Dialog>,
+    counter: Res<TurnCounter>
 ) {
         if !gamestate.changed {
             return;
@@ -57,11 +57,11 @@ pub fn gamestate_handler(
             Status::MenuOpen => {
                 dialog.free(&mut commands);
                 menu.reset_mode();
-                menu.render(&mut commands, &asset_server, &cam);
+                menu.render(&mut commands, &asset_server);
                 gamestate.changed = false;
             },
             Status::DialogOpen => {
-                dialog.render(&mut commands, &asset_server, &cam);
+                dialog.render(&mut commands, &asset_server, counter.get_turn());
                 menu.hide(&mut commands);
                 gamestate.changed = false;
             },
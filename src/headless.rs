@@ -0,0 +1,102 @@
+use bevy::app::AppExit;
+use bevy::prelude::{
+    App,
+    EventWriter,
+    MinimalPlugins,
+    ResMut,
+    Resource,
+    Update,
+};
+use serde_json::{json, Value};
+
+use crate::ecosystem::{get_display_name, PopulationStats};
+use crate::level::{Level, LevelBiome};
+
+#[derive(Resource)]
+struct HeadlessRun {
+    biome: String,
+    seed: u64,
+    turns: u32,
+    timeline: Vec<Value>,
+}
+
+/*
+ * Runs the simulation without a window, then prints statistics as JSON
+ *
+ * @param    string   biome to generate, such as "marsh"
+ * @param    u64      seed the random number generator was given
+ * @param    u32      number of turns to simulate
+ */
+pub fn run(biome: &str, seed: u64, turns: u32) {
+
+    let level_biome = match LevelBiome::parse(biome) {
+        Some(level_biome) => level_biome,
+        None => {
+            eprintln!("Unknown biome: {}", biome);
+            std::process::exit(1);
+        },
+    };
+
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .insert_resource(Level::new(level_biome))
+        .insert_resource(HeadlessRun {
+            biome: biome.to_lowercase(),
+            seed,
+            turns,
+            timeline: vec![],
+        })
+        .add_systems(Update, headless_handler)
+        .run();
+}
+
+fn get_stats_json(turn: u32, stats: &PopulationStats) -> Value {
+    json!({
+        "turn": turn,
+        "total": stats.total(),
+        "counts": stats.counts,
+        "births": stats.births,
+        "eaten": stats.eaten,
+        "killed": stats.killed,
+        "starved": stats.starved,
+        "collisions": stats.collisions,
+    })
+}
+
+// one turn per update, printing the results and exiting once every turn has run
+fn headless_handler(mut lvl: ResMut<Level>,
+                    mut run: ResMut<HeadlessRun>,
+                    mut exit: EventWriter<AppExit>) {
+
+    if lvl.get_turn() < run.turns {
+        lvl.next_turn();
+
+        if let Some(stats) = lvl.get_population_stats() {
+            let entry = get_stats_json(lvl.get_turn(), stats);
+            run.timeline.push(entry);
+        }
+        return;
+    }
+
+    let creatures: Vec<Value> = lvl.get_creatures().iter().map(|c| json!({
+        "species": get_display_name(c.get_species()),
+        "x": c.get_x(),
+        "y": c.get_y(),
+        "hp": c.get_hp(),
+        "hunger": c.get_hunger(),
+    })).collect();
+
+    let (x, y) = lvl.get_player_position();
+
+    let summary = json!({
+        "biome": run.biome,
+        "seed": run.seed,
+        "turns": lvl.get_turn(),
+        "player": { "x": x, "y": y },
+        "creatures": creatures,
+        "timeline": run.timeline,
+    });
+
+    println!("{}", summary);
+    exit.send(AppExit::Success);
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use crate::creature::Creature;
+use crate::decal::Decal;
+use crate::ecosystem::PopulationStats;
+
+// how many turns can be taken back
+pub const UNDO_LIMIT: usize = 100;
+
+// everything about the level that changes from one turn to the next
+#[derive(Clone)]
+pub struct Snapshot {
+    pub creatures: Vec<Creature>,
+    pub decals: Vec<Decal>,
+    pub player: (u32,u32),
+    pub turn: u32,
+    pub stats: Option<PopulationStats>,
+}
+
+pub struct History {
+    states: VecDeque<Snapshot>,
+    undone: Vec<Snapshot>,
+}
+
+impl History {
+
+    pub fn new() -> History {
+        History { states: VecDeque::new(), undone: vec![] }
+    }
+
+    /*
+     * Remembers the state of the level at the end of a turn; taking a new
+     * turn means the turns that were undone can no longer be redone
+     *
+     * @param    Snapshot   state of the level
+     */
+    pub fn record(&mut self, snapshot: Snapshot) {
+        self.states.push_back(snapshot);
+        self.undone.clear();
+
+        // the oldest state is kept as the point everything rolls back to
+        if self.states.len() > UNDO_LIMIT + 1 {
+            self.states.pop_front();
+        }
+    }
+
+    /*
+     * Forgets every turn, starting over from the given state
+     *
+     * @param    Snapshot   state of the level
+     */
+    pub fn reset(&mut self, snapshot: Snapshot) {
+        self.states.clear();
+        self.record(snapshot);
+    }
+
+    /*
+     * Steps back to the state before the latest turn
+     *
+     * @returns  Option<Snapshot>   state to restore, or None if there is
+     *                              nothing left to undo
+     */
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        if self.states.len() < 2 {
+            return None;
+        }
+
+        let latest = self.states.pop_back()?;
+        self.undone.push(latest);
+        self.states.back().cloned()
+    }
+
+    /*
+     * Steps forward to the state of the turn that was last undone
+     *
+     * @returns  Option<Snapshot>   state to restore, or None if there is
+     *                              nothing left to redo
+     */
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let next = self.undone.pop()?;
+        self.states.push_back(next.clone());
+        Some(next)
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::{
+    AssetServer,
+    BackgroundColor,
+    Color,
+    Commands,
+    Component,
+    DetectChanges,
+    Entity,
+    Node,
+    PositionType,
+    Query,
+    Res,
+    ResMut,
+    Resource,
+    Sprite,
+    Text as BevyText,
+    TextColor,
+    TextFont,
+    Transform,
+    UiRect,
+    Val,
+    Visibility,
+    With,
+    Without,
+};
+
+use crate::camera::Camera;
+use crate::constants::{HUD_FONT_SIZE, TOOLTIP_OFFSET, Z_VALUE_HIGHLIGHT};
+use crate::context::ContextMenu;
+use crate::cursor::Cursor;
+use crate::ecosystem::get_display_name;
+use crate::gamestate::{Gamestate, Status};
+use crate::level::Level;
+use crate::tile::TILE_SIZE;
+
+#[derive(Component)]
+pub struct HighlightEntity;
+
+#[derive(Component)]
+pub struct TooltipEntity;
+
+// the tooltip query also takes &mut Visibility, which Bevy rejects as a conflict (B0001) unless they are kept apart
+type HighlightOnly = (With<HighlightEntity>, Without<TooltipEntity>);
+
+#[derive(Resource)]
+pub struct Hover {
+    initialized: bool,
+    highlight: Entity,
+    tooltip: Entity,
+    cell: Option<(u32,u32)>,
+}
+
+impl Hover {
+
+    pub fn new() -> Hover {
+        Hover { initialized: false, highlight: Entity::from_raw(0), tooltip: Entity::from_raw(0), cell: None }
+    }
+
+    pub fn render(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>) {
+
+        if self.initialized {
+            commands.entity(self.highlight).despawn();
+            commands.entity(self.tooltip).despawn();
+            self.initialized = false;
+        }
+
+        self.highlight = commands.spawn((
+            Sprite::from_image(asset_server.load("img/ui/tile_highlight.png")),
+            Transform::from_xyz(0., 0., Z_VALUE_HIGHLIGHT),
+            Visibility::Hidden,
+        )).insert(HighlightEntity).id();
+
+        self.tooltip = commands.spawn((
+            BevyText::new(""),
+            TextFont {
+                font: asset_server.load("fonts/eight_bit.ttf"),
+                font_size: HUD_FONT_SIZE,
+                ..Default::default()
+            },
+            TextColor(Color::WHITE),
+            BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+            Node {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(4.)),
+                ..Default::default()
+            },
+            Visibility::Hidden,
+        )).insert(TooltipEntity).id();
+
+        self.cell = None;
+        self.initialized = true;
+    }
+
+    /*
+     * Describes what can be found in a grid cell
+     *
+     * @param    Level    the current level
+     * @param    u32      x position of the cell
+     * @param    u32      y position of the cell
+     *
+     * @returns  string   one line each for the tile, decal and anything standing there
+     */
+    pub fn get_content(lvl: &Level, x: u32, y: u32) -> String {
+        let mut lines = vec![];
+
+        // tile images are named after their biome and variant, such as "img/biomes/marsh/3.png"
+        if let Some(img) = lvl.get_tile_image(x, y) {
+            let variant = img.trim_start_matches("img/biomes/")
+                             .trim_end_matches(".png")
+                             .replace('/', " ");
+            lines.push(format!("Tile: {}", variant));
+        }
+
+        if let Some(decal) = lvl.get_decal_at(x, y) {
+            lines.push(format!("Decal: {}", get_display_name(decal.get_decal_type())));
+        }
+
+        if lvl.get_player_position() == (x, y) {
+            lines.push("Player".to_string());
+        }
+
+        if let Some(creature) = lvl.get_creature_at(x, y) {
+            lines.push(format!("Creature: {} ({} hp)", get_display_name(creature.get_species()), creature.get_hp()));
+        }
+
+        lines.join("\n")
+    }
+}
+
+// follows the mouse every frame, since the camera can move the level underneath it
+#[allow(clippy::too_many_arguments)]
+pub fn hover_handler(gamestate: Res<Gamestate>,
+                     cursor: Res<Cursor>,
+                     cam: Res<Camera>,
+                     lvl: Res<Level>,
+                     context_menu: Res<ContextMenu>,
+                     mut hover: ResMut<Hover>,
+                     mut highlights: Query<(&mut Transform, &mut Visibility), HighlightOnly>,
+                     mut tooltips: Query<(&mut BevyText, &mut Node, &mut Visibility), With<TooltipEntity>>) {
+
+    // the context menu covers up the level whilst it is open
+    let screen = match gamestate.get_status() {
+        Status::Playing if !context_menu.is_open() => cursor.get_screen_position(),
+        _ => None,
+    };
+
+    let cell = screen.and_then(|(x, y)| {
+        let (world_x, world_y) = cam.screen_to_world(x, y);
+        lvl.get_cell_at(world_x, world_y)
+    });
+
+    let moved = cell != hover.cell;
+    hover.cell = cell;
+
+    let visibility = match cell {
+        Some(_) => Visibility::Visible,
+        None => Visibility::Hidden,
+    };
+
+    for (mut transform, mut highlight_visibility) in highlights.iter_mut() {
+        if let Some((x, y)) = cell {
+            transform.translation.x = TILE_SIZE * x as f32;
+            transform.translation.y = TILE_SIZE * y as f32;
+        }
+        *highlight_visibility = visibility;
+    }
+
+    for (mut text, mut node, mut tooltip_visibility) in tooltips.iter_mut() {
+        if let (Some((x, y)), Some((screen_x, screen_y))) = (cell, screen) {
+
+            // creatures can come and go whilst the mouse stays put
+            if moved || lvl.is_changed() {
+                text.0 = Hover::get_content(&lvl, x, y);
+            }
+
+            node.left = Val::Px(screen_x + TOOLTIP_OFFSET);
+            node.top = Val::Px(screen_y + TOOLTIP_OFFSET);
+        }
+        *tooltip_visibility = visibility;
+    }
+}
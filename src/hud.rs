@@ -0,0 +1,113 @@
+use bevy::prelude::{
+    AssetServer,
+    Color,
+    Commands,
+    Component,
+    DetectChanges,
+    Entity,
+    Node,
+    PositionType,
+    Query,
+    Res,
+    Resource,
+    Text as BevyText,
+    TextColor,
+    TextFont,
+    Val,
+    Visibility,
+    With,
+};
+
+use crate::constants::HUD_FONT_SIZE;
+use crate::gamestate::{Gamestate, Status};
+use crate::level::Level;
+use crate::scheduler::TurnCounter;
+use crate::simulation::Simulation;
+
+#[derive(Component)]
+pub struct HudEntity;
+
+#[derive(Resource)]
+pub struct Hud {
+    initialized: bool,
+    entity: Entity,
+}
+
+impl Hud {
+
+    pub fn new() -> Hud {
+        Hud { initialized: false, entity: Entity::from_raw(0) }
+    }
+
+    pub fn render(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>) {
+
+        if self.initialized {
+            commands.entity(self.entity).despawn();
+            self.initialized = false;
+        }
+
+        self.entity = commands.spawn((
+            BevyText::new(""),
+            TextFont {
+                font: asset_server.load("fonts/eight_bit.ttf"),
+                font_size: HUD_FONT_SIZE,
+                ..Default::default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.),
+                left: Val::Px(8.),
+                ..Default::default()
+            },
+            Visibility::Hidden,
+        )).insert(HudEntity).id();
+
+        self.initialized = true;
+    }
+
+    /*
+     * Summarizes the state of the level in a few lines of text
+     *
+     * @param    Level        the current level
+     * @param    TurnCounter  number of turns played so far
+     * @param    Simulation   whether turns are advancing on their own
+     *
+     * @returns  string   text to display in the corner of the screen
+     */
+    pub fn get_content(lvl: &Level, counter: &TurnCounter, sim: &Simulation) -> String {
+        let mut lines = vec![format!("Turn {}  {}", counter.get_turn(), sim.get_label())];
+
+        if let Some(stats) = lvl.get_population_stats() {
+            lines.push(format!("Population {}", stats.total()));
+            for (name, count) in stats.counts.iter() {
+                lines.push(format!("{}: {}", name, count));
+            }
+            lines.push(format!("Born {}  Eaten {}  Killed {}  Starved {}", stats.births, stats.eaten, stats.killed, stats.starved));
+        }
+
+        lines.join("\n")
+    }
+}
+
+pub fn hud_handler(gamestate: Res<Gamestate>,
+                   lvl: Res<Level>,
+                   counter: Res<TurnCounter>,
+                   sim: Res<Simulation>,
+                   mut texts: Query<(&mut BevyText, &mut Visibility), With<HudEntity>>) {
+
+    if !gamestate.is_changed() && !lvl.is_changed() && !counter.is_changed() && !sim.is_changed() {
+        return;
+    }
+
+    for (mut text, mut visibility) in texts.iter_mut() {
+
+        // the menu and dialog sit on top of the level, so keep the HUD out of the way
+        *visibility = match gamestate.get_status() {
+            Status::Playing => Visibility::Visible,
+            _ => Visibility::Hidden,
+        };
+
+        text.0 = Hud::get_content(&lvl, &counter, &sim);
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::{
     AssetServer,
+    ButtonInput,
     Commands,
     EventReader,
     Res,
@@ -21,21 +22,41 @@ use bevy::{
     input::keyboard::KeyCode::ArrowRight,
     input::keyboard::KeyCode::ArrowLeft,
     input::keyboard::KeyCode::Escape,
+    input::keyboard::KeyCode,
+    input::keyboard::KeyCode::ShiftLeft,
+    input::keyboard::KeyCode::ShiftRight,
     input::keyboard::KeyCode::Space,
+    input::keyboard::KeyCode::KeyF,
+    input::keyboard::KeyCode::KeyP,
+    input::keyboard::KeyCode::KeyR,
+    input::keyboard::KeyCode::BracketLeft,
+    input::keyboard::KeyCode::BracketRight,
+    input::keyboard::KeyCode::ControlLeft,
+    input::keyboard::KeyCode::ControlRight,
+    input::keyboard::KeyCode::KeyY,
+    input::keyboard::KeyCode::KeyZ,
 };
 
-use crate::cursor::Cursor;
+use crate::camera::{Camera, Follow};
+use crate::context::ContextMenu;
 use crate::gamestate::{Gamestate, Status};
 use crate::menu::Menu;
 use crate::level::{Level, LevelBiome};
+use crate::selection::Selection;
+use crate::simulation::Simulation;
 
+#[allow(clippy::too_many_arguments)]
 pub fn keyboard_event_handler(mut commands: Commands,
-                          mut asset_server: Res<AssetServer>,
+                          asset_server: Res<AssetServer>,
                           mut event_reader: EventReader<KeyboardInput>,
-                          mut cursor: ResMut<Cursor>,
+                          keys: Res<ButtonInput<KeyCode>>,
+                          mut cam: ResMut<Camera>,
+                          mut context_menu: ResMut<ContextMenu>,
                           mut gamestate: ResMut<Gamestate>,
                           menu: ResMut<Menu>,
-                          mut lvl: ResMut<Level>) {
+                          mut lvl: ResMut<Level>,
+                          mut selection: ResMut<Selection>,
+                          mut sim: ResMut<Simulation>) {
 
     for event in event_reader.read() {
 
@@ -43,6 +64,16 @@ pub fn keyboard_event_handler(mut commands: Commands,
             Pressed => {
                 match event.key_code {
 
+                    // dismiss the context menu, leaving everything else as it was
+                    Escape if context_menu.is_open() => {
+                        context_menu.close(&mut commands);
+                    },
+
+                    // let go of the selected creatures before bringing up the menu
+                    Escape if gamestate.get_status() == Status::Playing && !selection.get_selected().is_empty() => {
+                        selection.clear();
+                    },
+
                     // exit
                     Escape => {
                         match menu.visible() {
@@ -55,21 +86,95 @@ pub fn keyboard_event_handler(mut commands: Commands,
                         }
                     },
 
-                    // hide the mouse whilst the camera is panning
-                    ArrowUp | KeyW | ArrowDown | KeyS | ArrowRight | KeyD | ArrowLeft | KeyA => {
-                        if gamestate.get_status() == Status::Playing {
-                            cursor.hide(&mut commands);
+                    // move the player
+                    ArrowUp | KeyW | ArrowDown | KeyS | ArrowRight | KeyD | ArrowLeft | KeyA
+                    if gamestate.get_status() == Status::Playing => {
+
+                        // shift pans the camera instead, see camera_event_handler
+                        if keys.any_pressed([ShiftLeft, ShiftRight]) {
+                            continue;
+                        }
+
+                        let direction = match event.key_code {
+                            ArrowUp | KeyW => (0,1),
+                            ArrowDown | KeyS => (0,-1),
+                            ArrowRight | KeyD => (1,0),
+                            _ => (-1,0),
+                        };
+
+                        if lvl.move_player(direction) {
+
+                            // in real-time mode the clock advances the turns instead
+                            if !sim.is_realtime() {
+                                lvl.next_turn();
+                                cam.shake_from(lvl.get_damage_events());
+                            }
+                            lvl.render(&mut commands, &asset_server);
+
+                            cam.set_follow(Some(Follow::Player));
+                        }
+                    },
+
+                    // advance a single turn, pausing the real-time mode if it is running
+                    Space if gamestate.get_status() == Status::Playing => {
+                        if sim.is_realtime() {
+                            sim.set_paused(true);
                         }
+                        lvl.next_turn();
+                        cam.shake_from(lvl.get_damage_events());
+                        lvl.render(&mut commands, &asset_server);
                     },
 
-                    // hide the mouse whilst the player is moving or pressing Space
-                    Space => {
-                        if gamestate.get_status() == Status::Playing {
-                            lvl.next_turn();
+                    // take back or replay a turn, pausing the real-time mode so it sticks
+                    KeyZ | KeyY
+                    if gamestate.get_status() == Status::Playing && keys.any_pressed([ControlLeft, ControlRight]) => {
+                        if sim.is_realtime() {
+                            sim.set_paused(true);
+                        }
+
+                        let changed = match event.key_code {
+                            KeyZ => lvl.undo(),
+                            _ => lvl.redo(),
+                        };
+
+                        if changed {
                             lvl.render(&mut commands, &asset_server);
+                            cam.set_follow(Some(Follow::Player));
                         }
                     },
 
+                    // have the camera follow each creature in turn, then back to the player
+                    KeyF if gamestate.get_status() == Status::Playing => {
+                        let after = match cam.get_follow() {
+                            Some(Follow::Creature(id)) => id,
+                            _ => 0,
+                        };
+                        let follow = match lvl.get_next_creature_id(after) {
+                            Some(id) => Follow::Creature(id),
+                            None => Follow::Player,
+                        };
+                        cam.set_follow(Some(follow));
+                    },
+
+                    // switch between turn-based and real-time mode
+                    KeyR if gamestate.get_status() == Status::Playing => {
+                        sim.toggle_realtime();
+                    },
+
+                    // pause or resume the real-time mode
+                    KeyP if gamestate.get_status() == Status::Playing && sim.is_realtime() => {
+                        let paused = sim.is_paused();
+                        sim.set_paused(!paused);
+                    },
+
+                    // adjust the speed of the real-time mode
+                    BracketLeft if gamestate.get_status() == Status::Playing => {
+                        sim.slower();
+                    },
+                    BracketRight if gamestate.get_status() == Status::Playing => {
+                        sim.faster();
+                    },
+
                     // switch biome
                     Digit1 => {
                         match lvl.get_biome() {
@@ -124,17 +229,7 @@ pub fn keyboard_event_handler(mut commands: Commands,
                 }
 
             },
-            Released => {
-                match event.key_code {
-
-                    // restore the mouse cursor once the camera stops
-                    ArrowUp | KeyW | ArrowDown | KeyS | ArrowRight | KeyD | ArrowLeft | KeyA => {
-                        cursor.render(&mut commands, &mut asset_server);
-                    },
-
-                    _ => (),
-                }
-            },
+            Released => (),
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,10 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::math::Vec2;
 use bevy::prelude::{
     Commands,
     AssetServer,
+    Entity,
     Transform,
     Res,
     Resource,
@@ -8,11 +12,27 @@ use bevy::prelude::{
     Visibility,
 };
 
-use crate::constants::{Z_VALUE_DECAL, Z_VALUE_TILE};
+use crate::animation::{get_frame_rect, Tween, WalkCycle};
+use crate::behaviour::Behaviour;
+use crate::combat::{get_damage, spawn_damage_number};
+use crate::constants::{LEVEL_HEIGHT, LEVEL_WIDTH, Z_VALUE_DECAL, Z_VALUE_PLAYER, Z_VALUE_TILE};
 use crate::decal::Decal;
-use crate::creature::Creature;
+use crate::creature::{Creature, NEIGHBOURS};
+use crate::ecosystem::{get_display_name, Diet, PopulationStats, MAX_CREATURES, POPULATION_HISTORY};
+use crate::history::{History, Snapshot};
+use crate::passability::Passability;
+use crate::pathfinding::Pathfinder;
+use crate::player::Player;
+use crate::scheduler::{get_initiative_order, Action, ACTION_COST};
+use crate::species::Species;
 use crate::tile::{Tile, TILE_SIZE};
-use crate::utils::random;
+use crate::utils::{distance, random};
+
+// how far away a wandering creature picks its next destination
+static WANDER_RADIUS: u32 = 5;
+
+// how much hunger an idle plant eater loses when nothing better is around
+static GRAZING: u32 = 8;
 
 pub enum LevelBiome {
     Desert,
@@ -23,6 +43,21 @@ pub enum LevelBiome {
     Space,
 }
 
+impl LevelBiome {
+
+    pub fn parse(biome: &str) -> Option<LevelBiome> {
+        match biome.to_lowercase().as_str() {
+            "desert" => Some(LevelBiome::Desert),
+            "grass" => Some(LevelBiome::Grass),
+            "ice" => Some(LevelBiome::Ice),
+            "marsh" => Some(LevelBiome::Marsh),
+            "snow" => Some(LevelBiome::Snow),
+            "space" => Some(LevelBiome::Space),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct Level {
     biome: LevelBiome,
@@ -31,6 +66,17 @@ pub struct Level {
     creature_types: Vec<String>,
     decals: Vec<Decal>,
     decal_types: Vec<String>,
+    passability: Passability,
+    pathfinder: Pathfinder,
+    species: HashMap<String, Species>,
+    default_species: Species,
+    turn: u32,
+    population_history: VecDeque<PopulationStats>,
+    stale_entities: Vec<Entity>,
+    damage_events: Vec<(u32,u32,u32)>,
+    next_id: u32,
+    player: Player,
+    history: History,
 }
 
 impl Level {
@@ -38,7 +84,8 @@ impl Level {
     pub fn new(biome: LevelBiome) -> Level {
 
         let min = 0;
-        let max = 22;
+        let width = LEVEL_WIDTH;
+        let height = LEVEL_HEIGHT;
         let mut coords: Vec<(u32,u32)> = vec![];
 
         let (biome_max, biome_folder, creature_types, decal_types) = Level::set_biome(&biome);
@@ -47,8 +94,8 @@ impl Level {
         // generate a grid of randomized tiles
         //
         let mut tiles: Vec<Tile> = Vec::new();
-        for x in min..max {
-            for y in min..max {
+        for x in min..width {
+            for y in min..height {
                 let img_num = random(1, biome_max);
                 let tile = Tile::new(x, y, [biome_folder, &img_num.to_string(), ".png"].concat());
                 tiles.push(tile);
@@ -61,7 +108,7 @@ impl Level {
         let mut decals: Vec<Decal> = Vec::new();
         for _ in 0..random(35,45) {
 
-            let xy = (random(min,max), random(min,max));
+            let xy = (random(min,width), random(min,height));
 
             // some very basic logic to skip decals that exists in the same (x,y)
             if coords.contains(&xy) {
@@ -82,17 +129,28 @@ impl Level {
                 ".png"
             ].concat();
 
-            let decal = Decal::new(xy.0,xy.1,img);
+            let decal = Decal::new(xy.0,xy.1,decal_types[random_decal_type].clone(),img);
             decals.push(decal);
         }
 
+        //
+        // place the player on the free cell closest to the middle of the map
+        //
+        let middle = (width / 2, height / 2);
+        let start = (min..width).flat_map(|x| (min..height).map(move |y| (x,y)))
+                                .filter(|xy| !coords.contains(xy))
+                                .min_by_key(|xy| distance(*xy, middle))
+                                .unwrap_or(middle);
+        coords.push(start);
+        let player = Player::new(start.0, start.1, "img/player/player_1.png".to_string());
+
         //
         // generate 6 to 12 random creatures
         //
         let mut creatures: Vec<Creature> = Vec::new();
         for _ in 0..random(6,12) {
 
-            let xy = (random(min,max), random(min,max));
+            let xy = (random(min,width), random(min,height));
 
             // some very basic logic to skip decals that exists in the same (x,y)
             if coords.contains(&xy) {
@@ -114,11 +172,48 @@ impl Level {
             ].concat();
 
             creatures.push(
-                Creature::new(xy.0,xy.1,img)
+                Creature::new(xy.0,xy.1,creature_types[random_creature_type].clone(),img)
             );
         }
 
-        Level { biome, tiles, creatures, creature_types, decals, decal_types }
+        let passability = Passability::new(width, height);
+
+        let pathfinder = Pathfinder::new();
+        let species = Species::load_all();
+        let default_species = Species::get_default();
+
+        let mut lvl = Level {
+            biome,
+            tiles,
+            creatures,
+            creature_types,
+            decals,
+            decal_types,
+            passability,
+            pathfinder,
+            species,
+            default_species,
+            turn: 0,
+            population_history: VecDeque::new(),
+            stale_entities: vec![],
+            damage_events: vec![],
+            next_id: 1,
+            player,
+            history: History::new(),
+        };
+        lvl.rebuild_passability();
+
+        for i in 0..lvl.creatures.len() {
+            let hp = lvl.get_species(lvl.creatures[i].get_species()).stats.hp;
+            lvl.creatures[i].set_hp(hp);
+            lvl.creatures[i].set_id(lvl.next_id);
+            lvl.next_id += 1;
+        }
+
+        let snapshot = lvl.snapshot();
+        lvl.history.reset(snapshot);
+
+        lvl
     }
 
     /*
@@ -237,6 +332,72 @@ impl Level {
         &self.biome
     }
 
+    /*
+     * Derives the passability map from the current tiles and decals
+     */
+    fn rebuild_passability(&mut self) {
+        for x in 0..self.get_width() {
+            for y in 0..self.get_height() {
+                self.update_passability(x, y);
+            }
+        }
+    }
+
+    /*
+     * Works out whether a single cell can be crossed and what it costs
+     *
+     * @param    u32    x position of the cell
+     * @param    u32    y position of the cell
+     */
+    fn update_passability(&mut self, x: u32, y: u32) {
+        // tiles are generated column by column, the same as the passability map
+        let tile_cost = match self.tiles.get((x * self.get_height() + y) as usize) {
+            Some(tile) => Tile::get_tile_movement_cost(&tile.img),
+            None => 1,
+        };
+
+        let (walkable, cost) = match self.decals.iter().find(|d| d.get_x() == x && d.get_y() == y) {
+            Some(decal) => (
+                !Decal::get_decal_type_blocking(decal.get_decal_type()),
+                tile_cost + Decal::get_decal_type_movement_cost(decal.get_decal_type()),
+            ),
+            None => (true, tile_cost),
+        };
+        self.passability.set_cell(x, y, walkable, cost);
+    }
+
+    /*
+     * Finds the closest cell something can stand on that nobody else has
+     *
+     * @param    (u32,u32)    cell to search outwards from
+     * @param    &[(u32,u32)] cells that are already taken
+     * @param    bool         true if blocking decals can be hovered over
+     *
+     * @returns  (u32,u32)    closest free cell, or the starting cell if none
+     */
+    fn get_nearest_free_cell(&self, from: (u32,u32), taken: &[(u32,u32)], flies: bool) -> (u32,u32) {
+        (0..self.get_width()).flat_map(|x| (0..self.get_height()).map(move |y| (x,y)))
+                             .filter(|xy| !taken.contains(xy) && (flies || self.is_walkable(xy.0, xy.1)))
+                             .min_by_key(|xy| distance(*xy, from))
+                             .unwrap_or(from)
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.passability.get_width()
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.passability.get_height()
+    }
+
+    pub fn is_walkable(&self, x: u32, y: u32) -> bool {
+        self.passability.is_walkable(x, y)
+    }
+
+    pub fn get_movement_cost(&self, x: u32, y: u32) -> u32 {
+        self.passability.get_cost(x, y)
+    }
+
     /*
      * Changes the biome type and associated details
      *
@@ -270,6 +431,17 @@ impl Level {
                 &img_num.to_string(),
                 ".png"
             ].concat());
+
+            // a new species starts its life from wherever it is standing
+            let home = (creature.get_x(), creature.get_y());
+            creature.set_species(self.creature_types[random_creature_type].clone());
+            creature.set_home(home);
+            creature.set_behaviour(Behaviour::Wander);
+            creature.set_goal(None);
+            creature.set_hunger(0);
+            creature.set_hp(
+                self.species.get(creature.get_species()).unwrap_or(&self.default_species).stats.hp
+            );
         }
 
         for decal in self.decals.iter_mut() {
@@ -287,13 +459,84 @@ impl Level {
                 &img_num.to_string(),
                 ".png"
             ].concat());
+            decal.set_decal_type(self.decal_types[random_decal_type].clone());
+        }
+
+        self.rebuild_passability();
+
+        //
+        // the new decals may have landed on someone, so move them off
+        //
+        let mut taken: Vec<(u32,u32)> = Vec::new();
+        let player = self.get_player_position();
+        let player = match self.is_walkable(player.0, player.1) {
+            true => player,
+            false => self.get_nearest_free_cell(player, &taken, false),
+        };
+        self.player.set_x(player.0);
+        self.player.set_y(player.1);
+        taken.push(player);
+
+        for i in 0..self.creatures.len() {
+            let flies = self.get_species(self.creatures[i].get_species()).flies;
+            let from = (self.creatures[i].get_x(), self.creatures[i].get_y());
+            let to = match !taken.contains(&from) && (flies || self.is_walkable(from.0, from.1)) {
+                true => from,
+                false => self.get_nearest_free_cell(from, &taken, flies),
+            };
+            let creature = &mut self.creatures[i];
+            creature.set_x(to.0);
+            creature.set_y(to.1);
+            creature.set_home(to);
+            taken.push(to);
         }
+
+        // turns taken in the old biome can't be brought back
+        let snapshot = self.snapshot();
+        self.history.reset(snapshot);
+    }
+
+    pub fn get_creatures(&self) -> &[Creature] {
+        &self.creatures
+    }
+
+    /*
+     * Finds where a creature currently is
+     *
+     * @param    u32                  id of the creature
+     *
+     * @returns  Option<(u32,u32)>    location, or None if it is gone
+     */
+    pub fn get_creature_location(&self, id: u32) -> Option<(u32,u32)> {
+        self.get_creature(id)
+            .map(|c| (c.get_x(), c.get_y()))
+    }
+
+    /*
+     * Steps through the creatures in the order they came into the world
+     *
+     * @param    u32           id to start after, or 0 to start from the beginning
+     *
+     * @returns  Option<u32>   id of the next creature, or None after the last one
+     */
+    pub fn get_next_creature_id(&self, after: u32) -> Option<u32> {
+        self.creatures.iter()
+                      .filter(|c| c.is_alive() && c.get_id() > after)
+                      .map(|c| c.get_id())
+                      .min()
+    }
+
+    pub fn get_damage_events(&self) -> &[(u32,u32,u32)] {
+        &self.damage_events
     }
 
-    pub fn get_creature_positions(&mut self) -> Vec<(u32,u32)> {
+    pub fn get_creature_positions(&self) -> Vec<(u32,u32)> {
         let mut positions: Vec<(u32,u32)> = vec![];
 
-        for c in self.creatures.iter_mut() {
+        for c in self.creatures.iter() {
+            if !c.is_alive() {
+                continue;
+            }
             let pos = (c.get_x(), c.get_y());
             positions.push(pos);
         }
@@ -301,23 +544,756 @@ impl Level {
         positions
     }
 
-    pub fn next_turn(&mut self) {
+    /*
+     * Finds the grid cell that covers a point in world space
+     *
+     * @param    f32                  x position in world space
+     * @param    f32                  y position in world space
+     *
+     * @returns  Option<(u32,u32)>    cell, or None if the point is off the level
+     */
+    pub fn get_cell_at(&self, x: f32, y: f32) -> Option<(u32,u32)> {
+
+        // tiles are drawn centered on their position
+        let cell_x = (x / TILE_SIZE).round();
+        let cell_y = (y / TILE_SIZE).round();
+
+        if cell_x < 0. || cell_y < 0. || cell_x >= self.get_width() as f32 || cell_y >= self.get_height() as f32 {
+            return None;
+        }
+
+        Some((cell_x as u32, cell_y as u32))
+    }
+
+    pub fn get_tile_image(&self, x: u32, y: u32) -> Option<&str> {
+        self.tiles.get((x * self.get_height() + y) as usize)
+                  .map(|t| t.img.as_str())
+    }
+
+    pub fn get_decal_at(&self, x: u32, y: u32) -> Option<&Decal> {
+        self.decals.iter()
+                   .find(|d| d.get_x() == x && d.get_y() == y)
+    }
+
+    pub fn get_creature(&self, id: u32) -> Option<&Creature> {
+        self.creatures.iter()
+                      .find(|c| c.get_id() == id && c.is_alive())
+    }
+
+    pub fn get_creature_at(&self, x: u32, y: u32) -> Option<&Creature> {
+        self.creatures.iter()
+                      .find(|c| c.is_alive() && c.get_x() == x && c.get_y() == y)
+    }
+
+    /*
+     * Takes the decal off a cell, which can be undone like a turn
+     *
+     * @param    u32    x position of the cell
+     * @param    u32    y position of the cell
+     *
+     * @returns  bool   true if there was a decal to remove
+     */
+    pub fn remove_decal(&mut self, x: u32, y: u32) -> bool {
+        let i = match self.decals.iter().position(|d| d.get_x() == x && d.get_y() == y) {
+            Some(i) => i,
+            None => return false,
+        };
+
+        let mut decal = self.decals.remove(i);
+        if decal.get_initialized() {
+            self.stale_entities.push(decal.get_entity());
+        }
+        self.rebuild_passability();
+
+        let snapshot = self.snapshot();
+        self.history.record(snapshot);
+        true
+    }
+
+    /*
+     * Places a creature of one of the biome's types on a free cell, which
+     * can be undone like a turn
+     *
+     * @param    u32           x position of the cell
+     * @param    u32           y position of the cell
+     *
+     * @returns  Option<u32>   id of the new creature, or None if the cell is
+     *                         taken or the level is full
+     */
+    pub fn spawn_creature(&mut self, x: u32, y: u32) -> Option<u32> {
+        if !self.is_walkable(x, y)
+        || self.get_creature_at(x, y).is_some()
+        || self.get_player_position() == (x, y)
+        || self.creatures.len() >= MAX_CREATURES {
+            return None;
+        }
+
+        let creature_type = self.creature_types[random(0, self.creature_types.len() as u32) as usize].clone();
+        let img_num = random(1, Creature::get_creature_type_max(&creature_type));
+        let img = ["img/creatures/", creature_type.as_str(), &img_num.to_string(), ".png"].concat();
+
+        let hp = self.get_species(&creature_type).stats.hp;
+        let mut creature = Creature::new(x, y, creature_type, img);
+        creature.set_hp(hp);
+        creature.set_id(self.next_id);
+        self.next_id += 1;
+
+        let id = creature.get_id();
+        self.creatures.push(creature);
+
+        let snapshot = self.snapshot();
+        self.history.record(snapshot);
+        Some(id)
+    }
+
+    /*
+     * Moves the home of some creatures, so that they head over there and
+     * stay close by, unless hunger or a threat gets in the way
+     *
+     * @param    u32[]        ids of the creatures
+     * @param    (u32,u32)    their new home
+     */
+    pub fn send_creatures(&mut self, ids: &[u32], home: (u32,u32)) {
+        for c in self.creatures.iter_mut().filter(|c| ids.contains(&c.get_id())) {
+            c.set_home(home);
+            c.set_goal(None);
+        }
+
+        let snapshot = self.snapshot();
+        self.history.record(snapshot);
+    }
+
+    pub fn get_species(&self, species_type: &str) -> &Species {
+        self.species.get(species_type).unwrap_or(&self.default_species)
+    }
+
+    /*
+     * Finds a path between two cells, avoiding every creature on the way
+     *
+     * @param    (u32,u32)           starting location
+     * @param    (u32,u32)           destination
+     * @param    bool                whether blocking decals can be flown over
+     *
+     * @returns  Option<(u32,u32)[]> steps to take, excluding the start
+     */
+    pub fn find_path(&mut self, start: (u32,u32), goal: (u32,u32), flies: bool) -> Option<Vec<(u32,u32)>> {
+        let occupied = self.get_occupied_positions();
+        self.pathfinder.find_path(&self.passability, start, goal, &occupied, flies)
+    }
+
+    /*
+     * Picks a random destination near the given location
+     *
+     * @param    (u32,u32)   current location
+     * @param    u32         maximum distance along either axis
+     *
+     * @returns  (u32,u32)   destination, kept within the level
+     */
+    fn random_destination(&self, from: (u32,u32), radius: u32) -> (u32,u32) {
+        let min_x = from.0.saturating_sub(radius);
+        let min_y = from.1.saturating_sub(radius);
+        let max_x = (from.0 + radius).min(self.get_width() - 1);
+        let max_y = (from.1 + radius).min(self.get_height() - 1);
+
+        (random(min_x, max_x + 1), random(min_y, max_y + 1))
+    }
+
+    /*
+     * Picks a destination that leads directly away from a threat
+     *
+     * @param    (u32,u32)   current location
+     * @param    (u32,u32)   location of the threat
+     * @param    u32         how far to run
+     *
+     * @returns  (u32,u32)   destination, kept within the level
+     */
+    fn flee_destination(&self, from: (u32,u32), threat: (u32,u32), radius: u32) -> (u32,u32) {
+        let away = |position: u32, threat: u32, max: u32| {
+            match position.cmp(&threat) {
+                std::cmp::Ordering::Less => position.saturating_sub(radius),
+                std::cmp::Ordering::Greater => (position + radius).min(max - 1),
+                std::cmp::Ordering::Equal => position,
+            }
+        };
+
+        (away(from.0, threat.0, self.get_width()), away(from.1, threat.1, self.get_height()))
+    }
+
+    /*
+     * Decides what the given creature is going to do this turn
+     *
+     * @param    usize               index of the creature
+     *
+     * @returns  Behaviour           the new behaviour
+     *           Option<(u32,u32)>   location the behaviour is aimed at
+     */
+    fn decide(&self, i: usize) -> (Behaviour, Option<(u32,u32)>) {
+        let c = &self.creatures[i];
+        let location = (c.get_x(), c.get_y());
+        let species = self.get_species(c.get_species());
+
+        let others: Vec<(&str,(u32,u32))> = self.creatures
+            .iter()
+            .enumerate()
+            .filter(|(j, o)| *j != i && o.is_alive())
+            .map(|(_, o)| (o.get_species(), (o.get_x(), o.get_y())))
+            .filter(|(_, xy)| distance(location, *xy) <= species.stats.sight)
+            .collect();
+
+        // creatures that don't need to eat hunt whenever they feel like it
+        let hungry = species.diet == Diet::None || c.get_hunger() * 3 >= species.max_hunger;
+
+        Behaviour::decide(location, c.get_home(), c.get_species(), species, hungry, &others)
+    }
+
+    /*
+     * Lets a plant eating creature graze on any nearby vegetation
+     *
+     * @param    usize   index of the creature
+     * @param    bool    whether the creature spent the turn idle
+     */
+    fn graze(&mut self, i: usize, idle: bool) {
+        let location = (self.creatures[i].get_x(), self.creatures[i].get_y());
+
+        let near_vegetation = self.decals.iter().any(|d| {
+            Decal::get_decal_type_edible(d.get_decal_type())
+                && distance(location, (d.get_x(), d.get_y())) <= 1
+        });
+
+        let hunger = self.creatures[i].get_hunger();
+        if near_vegetation {
+            self.creatures[i].set_hunger(0);
+        } else if idle {
+            self.creatures[i].set_hunger(hunger.saturating_sub(GRAZING));
+        }
+    }
+
+    /*
+     * Attempts to have a well fed creature give birth next to itself
+     *
+     * @param    usize            index of the creature
+     * @param    (u32,u32)[]      cells that are already occupied
+     *
+     * @returns  Option<Creature> the offspring, if any
+     */
+    fn breed(&mut self, i: usize, positions: &[(u32,u32)]) -> Option<Creature> {
+        let (flies, max_hunger, breed_chance, hp) = {
+            let species = self.get_species(self.creatures[i].get_species());
+            (species.flies, species.max_hunger, species.breed_chance, species.stats.hp)
+        };
+
+        let parent = &self.creatures[i];
+        if parent.get_hunger() * 4 > max_hunger || random(0, 100) >= breed_chance {
+            return None;
+        }
+
+        let width = self.get_width();
+        let height = self.get_height();
+        let nursery = NEIGHBOURS.iter()
+            .filter_map(|d| Creature::get_neighbour(parent.get_x(), parent.get_y(), *d, width, height))
+            .find(|xy| !positions.contains(xy) && (flies || self.is_walkable(xy.0, xy.1)))?;
+
+        let mut child = Creature::new(nursery.0, nursery.1, parent.get_species().to_string(), parent.img.clone());
+        child.set_hunger(max_hunger / 2);
+        child.set_hp(hp);
+
+        // ids are never reused, not even after an undo
+        child.set_id(self.next_id);
+        self.next_id += 1;
+
+        // raising young leaves the parent hungry
+        let hunger = self.creatures[i].get_hunger();
+        self.creatures[i].set_hunger(hunger + max_hunger / 4);
+
+        Some(child)
+    }
+
+    /*
+     * Gives a creature that isn't busy hunting or fleeing a chance to breed
+     *
+     * @param    usize            index of the creature
+     * @param    (u32,u32)[]      cells that are already occupied
+     * @param    Creature[]       offspring born so far this turn
+     * @param    PopulationStats  statistics of this turn
+     */
+    fn calm_turn(&mut self,
+                 i: usize,
+                 positions: &mut Vec<(u32,u32)>,
+                 offspring: &mut Vec<Creature>,
+                 stats: &mut PopulationStats) {
+
+        if offspring.len() + self.creatures.len() >= MAX_CREATURES {
+            return;
+        }
+
+        if let Some(child) = self.breed(i, positions) {
+            positions.push((child.get_x(), child.get_y()));
+            offspring.push(child);
+            stats.births += 1;
+        }
+    }
+
+    fn get_creature_index_at(&self, location: (u32,u32)) -> Option<usize> {
+        self.creatures.iter()
+                      .position(|c| c.is_alive() && (c.get_x(), c.get_y()) == location)
+    }
+
+    /*
+     * Has one creature strike another, killing it if it runs out of health
+     *
+     * @param    usize   index of the attacker
+     * @param    usize   index of the defender
+     *
+     * @returns  bool    true if the defender died
+     */
+    fn strike(&mut self, attacker: usize, defender: usize) -> bool {
+        let damage = get_damage(
+            &self.get_species(self.creatures[attacker].get_species()).stats,
+            &self.get_species(self.creatures[defender].get_species()).stats,
+        );
+
+        let target = &mut self.creatures[defender];
+        let hp = target.get_hp().saturating_sub(damage);
+        target.set_hp(hp);
+        self.damage_events.push((target.get_x(), target.get_y(), damage));
+
+        if hp == 0 {
+            self.die(defender);
+            return true;
+        }
+
+        false
+    }
+
+    /*
+     * Resolves a fight between two creatures, the faster one striking first
+     *
+     * @param    usize   index of the creature that started the fight
+     * @param    usize   index of the creature that was attacked
+     */
+    fn fight(&mut self, attacker: usize, defender: usize) {
+        let attacker_speed = self.get_species(self.creatures[attacker].get_species()).stats.speed;
+        let defender_speed = self.get_species(self.creatures[defender].get_species()).stats.speed;
+
+        let (first, second) = match defender_speed > attacker_speed {
+            true => (defender, attacker),
+            false => (attacker, defender),
+        };
+
+        if !self.strike(first, second) {
+            self.strike(second, first);
+        }
+    }
+
+    /*
+     * Kills a creature, leaving its bones behind on the ground
+     *
+     * @param    usize   index of the creature
+     */
+    fn die(&mut self, i: usize) {
+        self.creatures[i].kill();
+
+        let location = (self.creatures[i].get_x(), self.creatures[i].get_y());
+        if self.decals.iter().any(|d| (d.get_x(), d.get_y()) == location) {
+            return;
+        }
+
+        let img_num = random(1, Decal::get_decal_type_max("bones_") + 1);
+        let img = ["img/decals/bones_", &img_num.to_string(), ".png"].concat();
+        self.decals.push(Decal::new(location.0, location.1, "bones_".to_string(), img));
+
+        // only this cell changed, and bones leave it as easy to cross as before
+        self.update_passability(location.0, location.1);
+    }
+
+    pub fn get_turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn get_population_stats(&self) -> Option<&PopulationStats> {
+        self.population_history.back()
+    }
+
+    /*
+     * Gets every cell that is blocked by a creature or the player
+     *
+     * @returns  (u32,u32)[]   occupied cells
+     */
+    pub fn get_occupied_positions(&mut self) -> Vec<(u32,u32)> {
         let mut positions = self.get_creature_positions();
+        positions.push(self.get_player_position());
+        positions
+    }
+
+    pub fn get_player_position(&self) -> (u32,u32) {
+        (self.player.get_x(), self.player.get_y())
+    }
+
+    /*
+     * Moves the player one cell in the given direction
+     *
+     * @param    (i32,i32)   direction, such as (-1,0) for left
+     *
+     * @returns  bool        true if the player was able to move
+     */
+    pub fn move_player(&mut self, direction: (i32,i32)) -> bool {
+        let (x, y) = self.get_player_position();
+
+        let (nx, ny) = match Creature::get_neighbour(x, y, direction, self.get_width(), self.get_height()) {
+            Some(location) => location,
+            None => return false,
+        };
+
+        if !self.is_walkable(nx, ny) || self.get_creature_positions().contains(&(nx,ny)) {
+            return false;
+        }
+
+        self.player.set_x(nx);
+        self.player.set_y(ny);
+
+        true
+    }
+
+    /*
+     * Decides what a creature wants to do with its action
+     *
+     * @param    usize    index of the creature
+     *
+     * @returns  Action   the action to queue up
+     */
+    fn plan(&mut self, i: usize) -> Action {
+        let (flies, sight) = {
+            let species = self.get_species(self.creatures[i].get_species());
+            (species.flies, species.stats.sight)
+        };
+
+        let x = self.creatures[i].get_x();
+        let y = self.creatures[i].get_y();
+
+        let (behaviour, aim) = self.decide(i);
+        self.creatures[i].set_behaviour(behaviour);
+
+        let goal = match (behaviour, aim) {
+            (Behaviour::Idle, _) => {
+                self.creatures[i].set_goal(None);
+                return Action::Interact;
+            },
+            (Behaviour::Flee, Some(threat)) => self.flee_destination((x,y), threat, sight),
+            (_, Some(target)) => target,
+
+            // wander towards a nearby destination, picking a new one once it is reached
+            (_, None) => match self.creatures[i].get_goal() {
+                Some(goal) if goal != (x,y) => goal,
+                _ => self.random_destination((x,y), WANDER_RADIUS),
+            },
+        };
+        self.creatures[i].set_goal(Some(goal));
+
+        let step = match self.find_path((x,y), goal, flies) {
+            Some(path) if !path.is_empty() => path[0],
+            _ => {
+                let width = self.get_width();
+                let height = self.get_height();
+                self.creatures[i].set_goal(None);
+                self.creatures[i].next_turn(width, height)
+            },
+        };
+
+        if step == (x,y) {
+            return Action::Wait;
+        }
+
+        // bumping into a creature of another species starts a fight, whilst one
+        // of its own kind, such as a packmate, is waited out and walked around
+        match self.get_creature_index_at(step) {
+            Some(j) if self.creatures[j].get_species() == self.creatures[i].get_species() => {
+                self.creatures[i].set_goal(None);
+                Action::Wait
+            },
+            Some(_) => Action::Attack(step),
+            None => Action::Move(step),
+        }
+    }
+
+    /*
+     * Carries out a queued action, spending the energy it costs
+     *
+     * @param    usize            index of the creature
+     * @param    Action           the action to carry out
+     * @param    (u32,u32)[]      cells that are currently occupied
+     * @param    Creature[]       offspring born so far this turn
+     * @param    PopulationStats  statistics of this turn
+     */
+    fn resolve(&mut self,
+               i: usize,
+               action: Action,
+               positions: &mut Vec<(u32,u32)>,
+               offspring: &mut Vec<Creature>,
+               stats: &mut PopulationStats) {
+
+        let (flies, diet) = {
+            let species = self.get_species(self.creatures[i].get_species());
+            (species.flies, species.diet)
+        };
+
+        let x = self.creatures[i].get_x();
+        let y = self.creatures[i].get_y();
+        let energy = self.creatures[i].get_energy();
+        self.creatures[i].set_energy(energy - ACTION_COST);
+
+        match action {
+            Action::Wait => (),
+
+            Action::Interact => {
+                if diet == Diet::Plants {
+                    self.graze(i, true);
+                }
+                self.calm_turn(i, positions, offspring, stats);
+            },
+
+            Action::Attack(target) => {
+                self.creatures[i].set_goal(None);
+
+                // the target may have moved or died since the attack was planned
+                let j = match self.get_creature_index_at(target) {
+                    Some(j) if self.creatures[j].get_species() != self.creatures[i].get_species() => j,
+                    _ => return,
+                };
+
+                let hunting = self.creatures[i].get_behaviour() == Behaviour::Hunt
+                           && self.get_species(self.creatures[i].get_species())
+                                  .hunts.iter().any(|s| s == self.creatures[j].get_species());
 
+                self.fight(i, j);
+
+                if !self.creatures[j].is_alive() {
+                    positions.retain(|value| *value != target);
+
+                    // hunters that catch up with their prey eat it
+                    if hunting {
+                        self.creatures[i].set_hunger(0);
+                        stats.eaten += 1;
+                    } else {
+                        stats.killed += 1;
+                    }
+                }
+
+                if !self.creatures[i].is_alive() {
+                    positions.retain(|value| *value != (x,y));
+                    stats.killed += 1;
+                }
+            },
+
+            Action::Move((nx, ny)) => {
+
+                // a faster creature may have claimed the cell in the meantime
+                if positions.contains(&(nx,ny)) {
+                    self.creatures[i].set_goal(None);
+                    stats.collisions += 1;
+                    return;
+                }
+
+                if !(flies || self.is_walkable(nx, ny)) {
+                    self.creatures[i].set_goal(None);
+                    return;
+                }
+
+                // difficult terrain takes more than one action's worth of energy
+                let cost = match flies {
+                    true => 1,
+                    false => self.get_movement_cost(nx, ny),
+                };
+
+                let c = &mut self.creatures[i];
+                c.set_x(nx);
+                c.set_y(ny);
+                c.set_energy(energy - ACTION_COST * cost as i32);
+
+                positions.retain(|value| *value != (x,y));
+                positions.push((nx,ny));
+
+                if diet == Diet::Plants {
+                    self.graze(i, false);
+                }
+                if self.creatures[i].get_behaviour() == Behaviour::Wander {
+                    self.calm_turn(i, positions, offspring, stats);
+                }
+            },
+        }
+    }
+
+    pub fn next_turn(&mut self) {
+        self.turn += 1;
+
+        // only the damage dealt during the latest turn is ever shown
+        self.damage_events.clear();
+
+        let mut stats = PopulationStats::new();
+        let mut offspring: Vec<Creature> = vec![];
+        let mut positions = self.get_occupied_positions();
+
+        for i in 0..self.creatures.len() {
+
+            let (speed, diet, max_hunger) = {
+                let species = self.get_species(self.creatures[i].get_species());
+                (species.stats.speed, species.diet, species.max_hunger)
+            };
+
+            // faster creatures build up the energy needed to act more quickly
+            let energy = self.creatures[i].get_energy();
+            self.creatures[i].set_energy(energy + speed as i32);
+
+            // everything that eats gets a little hungrier each turn
+            if diet != Diet::None {
+                let hunger = self.creatures[i].get_hunger() + 1;
+                self.creatures[i].set_hunger(hunger);
+
+                if hunger >= max_hunger {
+                    let location = (self.creatures[i].get_x(), self.creatures[i].get_y());
+                    self.die(i);
+                    positions.retain(|value| *value != location);
+                    stats.starved += 1;
+                }
+            }
+        }
+
+        // every creature with enough energy queues up an action, then the
+        // queue resolves in order of initiative; repeat until nobody is left
+        // with enough energy, so very fast creatures may act more than once
+        loop {
+            let ready: Vec<(usize, i32, u32)> = self.creatures
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.is_alive() && c.get_energy() >= ACTION_COST)
+                .map(|(i, c)| (i, c.get_energy(), self.get_species(c.get_species()).stats.speed))
+                .collect();
+
+            if ready.is_empty() {
+                break;
+            }
+
+            let mut queue: Vec<(usize, Action)> = vec![];
+            for i in get_initiative_order(&ready) {
+                queue.push((i, self.plan(i)));
+            }
+
+            for (i, action) in queue {
+                if self.creatures[i].is_alive() {
+                    self.resolve(i, action, &mut positions, &mut offspring, &mut stats);
+                }
+            }
+        }
+
+        // clear away the dead, remembering which sprites need to be despawned
         for c in self.creatures.iter_mut() {
-            let x = c.get_x();
-            let y = c.get_y();
-            let (nx, ny) = c.next_turn();
+            if !c.is_alive() && c.get_initialized() {
+                self.stale_entities.push(c.get_entity());
+            }
+        }
+        self.creatures.retain(|c| c.is_alive());
+        self.creatures.append(&mut offspring);
 
-            if positions.contains(&(nx,ny)) {
-                continue;
+        for c in self.creatures.iter() {
+            *stats.counts.entry(get_display_name(c.get_species())).or_insert(0) += 1;
+        }
+
+        self.population_history.push_back(stats);
+        if self.population_history.len() > POPULATION_HISTORY {
+            self.population_history.pop_front();
+        }
+
+        let snapshot = self.snapshot();
+        self.history.record(snapshot);
+    }
+
+    /*
+     * Captures everything a turn is able to change
+     *
+     * @returns  Snapshot   state of the level
+     */
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            creatures: self.creatures.clone(),
+            decals: self.decals.clone(),
+            player: self.get_player_position(),
+            turn: self.turn,
+            stats: self.population_history.back().cloned(),
+        }
+    }
+
+    /*
+     * Puts the level back into a previously captured state
+     *
+     * @param    Snapshot   state of the level
+     */
+    fn restore(&mut self, snapshot: Snapshot) {
+
+        // the sprites on screen belong to the current state, so clear them away
+        for c in self.creatures.iter_mut() {
+            if c.get_initialized() {
+                self.stale_entities.push(c.get_entity());
             }
+        }
+        for d in self.decals.iter_mut() {
+            if d.get_initialized() {
+                self.stale_entities.push(d.get_entity());
+            }
+        }
+
+        self.creatures = snapshot.creatures;
+        self.decals = snapshot.decals;
+        self.turn = snapshot.turn;
+        self.player.set_x(snapshot.player.0);
+        self.player.set_y(snapshot.player.1);
+        self.damage_events.clear();
+
+        for c in self.creatures.iter_mut() {
+            let location = (c.get_x(), c.get_y());
+            c.set_drawn(location);
+            c.set_initialized(false);
+        }
+        for d in self.decals.iter_mut() {
+            d.set_initialized(false);
+        }
+
+        self.rebuild_passability();
+    }
+
+    /*
+     * Takes back the latest turn
+     *
+     * @returns  bool   true if there was a turn to take back
+     */
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo() {
+            Some(snapshot) => {
 
-            c.set_x(nx);
-            c.set_y(ny);
+                // edits made in the editor are undone too, but only turns have stats of their own
+                if snapshot.turn < self.turn {
+                    self.population_history.pop_back();
+                }
+                self.restore(snapshot);
+                true
+            },
+            None => false,
+        }
+    }
 
-            positions.retain(|value| *value != (x,y));
-            positions.push((nx,ny));
+    /*
+     * Replays the turn that was last taken back
+     *
+     * @returns  bool   true if there was a turn to replay
+     */
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo() {
+            Some(snapshot) => {
+                if let (Some(stats), true) = (snapshot.stats.clone(), snapshot.turn > self.turn) {
+                    self.population_history.push_back(stats);
+                }
+                self.restore(snapshot);
+                true
+            },
+            None => false,
         }
     }
 
@@ -325,6 +1301,8 @@ impl Level {
                   commands: &mut Commands,
                   asset_server: &Res<AssetServer>) {
 
+        self.render_aftermath(commands, asset_server);
+
         //
         // TILES
         //
@@ -385,30 +1363,280 @@ impl Level {
         //
         // CREATURES
         //
-        for creature in self.creatures.iter_mut() {
+        for i in 0..self.creatures.len() {
+            self.render_creature(i, commands, asset_server);
+        }
+
+        //
+        // PLAYER
+        //
+        if self.player.get_initialized() {
+            commands.entity(self.player.get_entity()).despawn();
+            self.player.set_initialized(false);
+        }
 
-            if creature.get_initialized() {
-                commands.entity(creature.get_entity()).despawn();
-                creature.set_initialized(false);
+        let x = self.player.get_x();
+        let y = self.player.get_y();
+
+        self.player.set_entity(commands.spawn((
+                Sprite::from_image(
+                    asset_server.load(&self.player.img)
+                ),
+                Visibility::Visible,
+                Transform::from_xyz(
+                    TILE_SIZE * x as f32,
+                    TILE_SIZE * y as f32,
+                    Z_VALUE_PLAYER
+                ),
+            )).id()
+        );
+
+        self.player.set_initialized(true);
+    }
+
+    /*
+     * Brings the sprites up to date after a turn, leaving the tiles, decals
+     * and player alone, since only the creatures change whilst time passes
+     *
+     * @param    Commands
+     * @param    AssetServer
+     */
+    pub fn render_turn(&mut self,
+                       commands: &mut Commands,
+                       asset_server: &Res<AssetServer>) {
+
+        self.render_aftermath(commands, asset_server);
+
+        // newborns have no sprite yet, and creatures that moved need to walk over
+        for i in 0..self.creatures.len() {
+            let creature = &mut self.creatures[i];
+            let outdated = !creature.get_initialized() || creature.get_drawn() != (creature.get_x(), creature.get_y());
+
+            if outdated {
+                self.render_creature(i, commands, asset_server);
             }
+        }
+    }
 
-            let x = creature.get_x();
-            let y = creature.get_y();
+    fn render_aftermath(&mut self,
+                        commands: &mut Commands,
+                        asset_server: &Res<AssetServer>) {
 
-            creature.set_entity(commands.spawn((
-                    Sprite::from_image(
-                        asset_server.load(&creature.img)
-                    ),
-                    Visibility::Visible,
-                    Transform::from_xyz(
-                        TILE_SIZE * x as f32,
-                        TILE_SIZE * y as f32,
-                        Z_VALUE_DECAL
-                    ),
-                )).id()
-            );
+        // sprites of creatures that are no longer part of the level
+        for entity in self.stale_entities.drain(..) {
+            commands.entity(entity).despawn();
+        }
+
+        // creature sprites are two tiles tall, so float the numbers above their heads
+        for (x, y, amount) in self.damage_events.drain(..) {
+            spawn_damage_number(commands,
+                                asset_server,
+                                TILE_SIZE * x as f32,
+                                TILE_SIZE * y as f32 + TILE_SIZE,
+                                amount);
+        }
+    }
+
+    fn render_creature(&mut self,
+                       i: usize,
+                       commands: &mut Commands,
+                       asset_server: &Res<AssetServer>) {
 
-            creature.set_initialized(true);
+        let (walk_sheet, walk_frames) = {
+            let species = self.get_species(self.creatures[i].get_species());
+            (species.walk_sheet.clone(), species.walk_frames)
+        };
+
+        let creature = &mut self.creatures[i];
+
+        if creature.get_initialized() {
+            commands.entity(creature.get_entity()).despawn();
+            creature.set_initialized(false);
+        }
+
+        let x = creature.get_x();
+        let y = creature.get_y();
+        let (from_x, from_y) = creature.get_drawn();
+
+        // face whichever way the creature last moved
+        if x != from_x {
+            creature.set_facing_left(x < from_x);
+        }
+
+        // species with a walk cycle are drawn from their sprite sheet instead
+        let mut sprite = match &walk_sheet {
+            Some(sheet) if walk_frames > 1 => Sprite {
+                image: asset_server.load(sheet),
+                rect: Some(get_frame_rect(0)),
+                ..Default::default()
+            },
+            _ => Sprite::from_image(asset_server.load(&creature.img)),
+        };
+        sprite.flip_x = creature.get_facing_left();
+
+        let mut entity = commands.spawn((
+                sprite,
+                Visibility::Visible,
+                Transform::from_xyz(
+                    TILE_SIZE * from_x as f32,
+                    TILE_SIZE * from_y as f32,
+                    Z_VALUE_DECAL
+                ),
+            ));
+
+        // the grid position has already changed, only the sprite catches up
+        if (x, y) != (from_x, from_y) {
+            entity.insert(Tween::new(
+                Vec2::new(TILE_SIZE * from_x as f32, TILE_SIZE * from_y as f32),
+                Vec2::new(TILE_SIZE * x as f32, TILE_SIZE * y as f32),
+            ));
+
+            if walk_sheet.is_some() && walk_frames > 1 {
+                entity.insert(WalkCycle::new(walk_frames));
+            }
         }
+
+        creature.set_entity(entity.id());
+        creature.set_drawn((x, y));
+        creature.set_initialized(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_X: f32 = (LEVEL_WIDTH - 1) as f32;
+    const MAX_Y: f32 = (LEVEL_HEIGHT - 1) as f32;
+
+    // a level with nothing on it but the given creatures
+    fn level_with(creatures: &[(&str,u32,u32)]) -> Level {
+        let mut lvl = Level::new(LevelBiome::Grass);
+        lvl.decals.clear();
+        lvl.creatures.clear();
+        lvl.rebuild_passability();
+
+        for (species, x, y) in creatures.iter() {
+            let hp = lvl.get_species(species).stats.hp;
+            let mut creature = Creature::new(*x, *y, species.to_string(), String::new());
+            creature.set_hp(hp);
+            creature.set_id(lvl.next_id);
+            lvl.next_id += 1;
+            lvl.creatures.push(creature);
+        }
+
+        lvl
+    }
+
+    #[test]
+    fn changing_biome_moves_everyone_off_blocked_cells() {
+        for biome in [LevelBiome::Desert, LevelBiome::Ice, LevelBiome::Marsh, LevelBiome::Space] {
+            let mut lvl = level_with(&[("wolf_", 10, 10), ("wolf_", 11, 10)]);
+
+            // a decal under everyone, so some are bound to end up blocking
+            let mut cells = lvl.get_creature_positions();
+            cells.push(lvl.get_player_position());
+            for (x, y) in cells {
+                lvl.decals.push(Decal::new(x, y, String::new(), String::new()));
+            }
+            lvl.change(biome);
+
+            let player = lvl.get_player_position();
+            assert!(lvl.is_walkable(player.0, player.1));
+            for creature in lvl.creatures.iter() {
+                let xy = (creature.get_x(), creature.get_y());
+                assert_ne!(xy, player);
+                assert!(lvl.get_species(creature.get_species()).flies || lvl.is_walkable(xy.0, xy.1));
+            }
+        }
+    }
+
+    #[test]
+    fn boggy_tiles_are_slower_than_the_ground_around_them() {
+        let mut lvl = level_with(&[]);
+        lvl.tiles[0].set_image("img/biomes/marsh/1.png".to_string());
+        lvl.tiles[1].set_image("img/biomes/marsh/2.png".to_string());
+        lvl.rebuild_passability();
+
+        assert_eq!(lvl.get_movement_cost(0, 0), 2);
+        assert_eq!(lvl.get_movement_cost(0, 1), 1);
+    }
+
+    #[test]
+    fn dying_keeps_the_cached_paths() {
+        let mut lvl = level_with(&[("wolf_", 10, 10)]);
+        let revision = lvl.passability.get_revision();
+
+        lvl.die(0);
+
+        assert!(lvl.decals.iter().any(|d| (d.get_x(), d.get_y()) == (10,10)));
+        assert_eq!(lvl.passability.get_revision(), revision);
+    }
+
+    #[test]
+    fn packmates_never_fight() {
+        let mut lvl = level_with(&[("wolf_", 10, 10), ("wolf_", 11, 10)]);
+
+        // each one wandering straight into the other
+        lvl.creatures[0].set_goal(Some((11,10)));
+        lvl.creatures[1].set_goal(Some((10,10)));
+
+        for _ in 0..100 {
+            lvl.next_turn();
+            assert!(lvl.get_damage_events().is_empty(), "wolves attacked each other on turn {}", lvl.turn);
+        }
+    }
+
+    #[test]
+    fn undoing_an_edit_keeps_the_population_history() {
+        let mut lvl = level_with(&[("wolf_", 10, 10)]);
+        lvl.next_turn();
+        lvl.next_turn();
+        lvl.send_creatures(&[1], (4,4));
+
+        assert!(lvl.undo());
+        assert_eq!(lvl.population_history.len(), 2);
+        assert!(lvl.redo());
+        assert_eq!(lvl.population_history.len(), 2);
+
+        assert!(lvl.undo());
+        assert!(lvl.undo());
+        assert_eq!(lvl.population_history.len(), 1);
+        assert!(lvl.redo());
+        assert_eq!(lvl.population_history.len(), 2);
+    }
+
+    #[test]
+    fn cell_centers() {
+        let lvl = Level::new(LevelBiome::Grass);
+        assert_eq!(lvl.get_cell_at(0., 0.), Some((0,0)));
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * 3., TILE_SIZE * 5.), Some((3,5)));
+    }
+
+    #[test]
+    fn cells_round_to_the_nearest_tile() {
+        let lvl = Level::new(LevelBiome::Grass);
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * 3.49, TILE_SIZE * 4.51), Some((3,5)));
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * 0.5, 0.), Some((1,0)));
+    }
+
+    #[test]
+    fn cells_at_the_borders() {
+        let lvl = Level::new(LevelBiome::Grass);
+
+        // tiles are centered on their position, so the outer half of the edge tiles reaches past zero
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * -0.49, TILE_SIZE * -0.49), Some((0,0)));
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * (MAX_X + 0.49), TILE_SIZE * (MAX_Y + 0.49)),
+                   Some((LEVEL_WIDTH - 1, LEVEL_HEIGHT - 1)));
+    }
+
+    #[test]
+    fn off_the_level() {
+        let lvl = Level::new(LevelBiome::Grass);
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * -0.51, 0.), None);
+        assert_eq!(lvl.get_cell_at(0., TILE_SIZE * -0.51), None);
+        assert_eq!(lvl.get_cell_at(TILE_SIZE * (MAX_X + 0.51), 0.), None);
+        assert_eq!(lvl.get_cell_at(0., TILE_SIZE * (MAX_Y + 0.51)), None);
+        assert_eq!(lvl.get_cell_at(-1000., 1000.), None);
     }
 }
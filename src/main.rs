@@ -1,22 +1,51 @@
+mod animation;
+use animation::tween_handler;
+
+mod args;
+use args::Args;
+
+mod behaviour;
+
 mod camera;
 use camera::{Camera, camera_event_handler};
 
+mod combat;
+use combat::floating_text_handler;
+
 mod constants;
 use constants::{CAMERA_HIGHEST_LEVEL, SCREEN_HEIGHT, SCREEN_WIDTH};
 
+mod context;
+use context::{ContextAction, ContextMenu};
+
 mod creature;
 
 mod cursor;
-use cursor::{Cursor, mouse_event_handler};
+use cursor::{Cursor, cursor_position_handler, cursor_state_handler, mouse_event_handler};
 
 mod decal;
 
 mod dialog;
 use dialog::Dialog;
 
+mod ecosystem;
+
+mod editor;
+use editor::editor_handler;
+
 mod gamestate;
 use gamestate::{Gamestate, gamestate_handler};
 
+mod history;
+
+mod hover;
+use hover::{Hover, hover_handler};
+
+mod headless;
+
+mod hud;
+use hud::{Hud, hud_handler};
+
 mod menu;
 use menu::Menu;
 
@@ -24,6 +53,7 @@ mod keyboard;
 use keyboard::keyboard_event_handler;
 
 mod tile;
+use tile::TILE_SIZE;
 
 mod level;
 use level::{Level, LevelBiome};
@@ -31,6 +61,26 @@ use level::{Level, LevelBiome};
 mod options;
 use options::get_options;
 
+mod passability;
+
+mod pathfinding;
+
+mod player;
+
+mod replay;
+use replay::{Recorder, Replay, playback_handler, record_handler};
+
+mod scheduler;
+use scheduler::{TurnCounter, turn_counter_handler};
+
+mod selection;
+use selection::{Selection, selection_handler};
+
+mod simulation;
+use simulation::{Simulation, simulation_handler, simulation_rate_handler};
+
+mod species;
+
 mod text;
 
 mod ui;
@@ -42,11 +92,15 @@ use bevy::{prelude::{
     AssetServer,
     Commands,
     DefaultPlugins,
+    First,
+    FixedUpdate,
     ImagePlugin,
+    IntoSystemConfigs,
+    Last,
     PluginGroup,
     Res,
     ResMut, Startup, Update,
-}, window::{
+}, time::TimeSystem, window::{
     CursorOptions as BevyCursor,
     MonitorSelection,
     PresentMode,
@@ -58,6 +112,31 @@ use bevy::{prelude::{
 
 fn main() {
 
+    let args = Args::parse();
+
+    // a replay brings its own seed, otherwise pick one so the session can be recorded
+    let replay = args.replay.as_deref().and_then(Replay::load);
+    if args.replay.is_some() && replay.is_none() {
+        println!("Unable to read the replay file, starting a new session instead.");
+    }
+
+    let seed = match &replay {
+        Some(replay) => replay.get_seed(),
+        None => args.seed.unwrap_or_else(|| fastrand::u64(..)),
+    };
+    utils::seed(seed);
+
+    if args.headless {
+        headless::run(&args.biome, seed, args.turns);
+        return;
+    }
+
+    let recorder = match (replay, args.record) {
+        (Some(replay), _) => Recorder::playback(replay),
+        (None, Some(path)) => Recorder::record(path, seed),
+        (None, None) => Recorder::new(),
+    };
+
     let current_options = get_options();
 
     let mode: WindowMode = if current_options.fullscreen && current_options.borderless { WindowMode::BorderlessFullscreen(MonitorSelection::Primary) }
@@ -80,6 +159,12 @@ fn main() {
         ..Default::default()
     };
 
+    // each subsystem offers its own items when right-clicking the level
+    let mut context_menu = ContextMenu::new();
+    selection::register_context_items(&mut context_menu);
+    camera::register_context_items(&mut context_menu);
+    editor::register_context_items(&mut context_menu);
+
     App::new()
 
         .add_plugins(DefaultPlugins
@@ -99,33 +184,71 @@ fn main() {
         )
 
         .insert_resource(Camera::new(320.0, 320.0, CAMERA_HIGHEST_LEVEL, SCREEN_HEIGHT, SCREEN_WIDTH))
-        .insert_resource(Cursor::new("img/ui/mouse_gfx.png".to_string()))
+        .insert_resource(context_menu)
+        .insert_resource(Cursor::new(current_options.hide_cursor_when_panning))
         .insert_resource(Dialog::new())
         .insert_resource(Gamestate::new())
+        .insert_resource(Hover::new())
+        .insert_resource(Hud::new())
         .insert_resource(Menu::new("img/ui/menu_main.png".to_string()))
         .insert_resource(Level::new(LevelBiome::Marsh))
+        .insert_resource(recorder)
+        .insert_resource(Selection::new())
+        .insert_resource(Simulation::new())
+        .insert_resource(TurnCounter::new())
+
+        .add_event::<ContextAction>()
 
         .add_systems(Startup, setup)
 
+        .add_systems(First, playback_handler.before(TimeSystem))
+
         .add_systems(Update, gamestate_handler)
         .add_systems(Update, camera_event_handler)
         .add_systems(Update, keyboard_event_handler)
         .add_systems(Update, mouse_event_handler)
+        .add_systems(Update, turn_counter_handler)
+        .add_systems(Update, hover_handler.after(camera_event_handler))
+        .add_systems(Update, selection_handler.after(camera_event_handler).before(mouse_event_handler))
+        .add_systems(Update, editor_handler)
+        .add_systems(Update, cursor_position_handler.after(camera_event_handler))
+        .add_systems(Update, cursor_state_handler.after(camera_event_handler).after(mouse_event_handler))
+        .add_systems(Update, hud_handler)
+        .add_systems(Update, floating_text_handler)
+        .add_systems(Update, tween_handler)
+        .add_systems(Update, simulation_rate_handler)
+
+        .add_systems(FixedUpdate, simulation_handler)
+
+        .add_systems(Last, record_handler)
 
         .run();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn setup(mut commands: Commands,
-         mut asset_server: Res<AssetServer>,
+         asset_server: Res<AssetServer>,
          mut cam: ResMut<Camera>,
          mut cursor: ResMut<Cursor>,
          mut dialog: ResMut<Dialog>,
+         mut hover: ResMut<Hover>,
+         mut hud: ResMut<Hud>,
          mut menu: ResMut<Menu>,
+         mut selection: ResMut<Selection>,
          mut lvl: ResMut<Level>) {
 
+    // start off centered on the player, without showing anything past the level
+    let (x, y) = lvl.get_player_position();
+    cam.set_position(TILE_SIZE * x as f32, TILE_SIZE * y as f32);
+    cam.set_bounds(lvl.get_width(), lvl.get_height());
+    cam.clamp_to_bounds();
+
     cam.start(&mut commands);
     dialog.load_dialog(&mut commands, 1);
-    menu.render(&mut commands, &asset_server, &cam);
-    cursor.render(&mut commands, &mut asset_server);
+    menu.render(&mut commands, &asset_server);
+    cursor.render(&mut commands, &asset_server);
+    hover.render(&mut commands, &asset_server);
+    hud.render(&mut commands, &asset_server);
+    selection.render(&mut commands, &asset_server);
     lvl.render(&mut commands, &asset_server);
 }
@@ -9,13 +9,14 @@ use bevy::prelude::{
     Sprite,
     Transform,
 };
+use bevy::render::view::RenderLayers;
 
 //static MODE_DEFAULT: i8 = 0;
 static MODE_CONTINUE: i8 = 1;
 static MODE_OPTIONS: i8 = 2;
 static MODE_QUIT: i8 = 3;
 
-use crate::camera::Camera;
+use crate::constants::UI_LAYER;
 use crate::constants::Z_VALUE_MENU;
 use crate::constants::Z_VALUE_MENU_ELEMENTS;
 use crate::gamestate::Gamestate;
@@ -49,8 +50,7 @@ impl Menu {
 
     pub fn render(&mut self,
                   commands: &mut Commands,
-                  asset_server: &Res<AssetServer>,
-                  camera: &ResMut<Camera>) {
+                  asset_server: &Res<AssetServer>) {
 
         // as a precaution, clear away all existing elements, if any
         self.hide(commands);
@@ -74,8 +74,8 @@ impl Menu {
                                                      66.);
                 continue_button.render(commands,
                                        asset_server,
-                                       camera.get_x(),
-                                       camera.get_y()+44.,
+                                       0.,
+                                       44.,
                                        Z_VALUE_MENU_ELEMENTS);
 
                 let mut save_button = UI::new(String::from("Save"),
@@ -85,8 +85,8 @@ impl Menu {
                                                  66.);
                 save_button.render(commands,
                                    asset_server,
-                                   camera.get_x(),
-                                   camera.get_y()+20.0,
+                                   0.,
+                                   20.0,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 let mut load_button = UI::new(String::from("Load"),
@@ -96,8 +96,8 @@ impl Menu {
                                                66.);
                 load_button.render(commands,
                                    asset_server,
-                                   camera.get_x(),
-                                   camera.get_y()-4.0,
+                                   0.,
+                                   -4.0,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 let mut options_button = UI::new(String::from("Options"),
@@ -107,8 +107,8 @@ impl Menu {
                                                     66.);
                 options_button.render(commands,
                                       asset_server,
-                                      camera.get_x(),
-                                      camera.get_y()-28.0,
+                                      0.,
+                                      -28.0,
                                       Z_VALUE_MENU_ELEMENTS);
 
                 let mut quit_button = UI::new(String::from("Quit"),
@@ -118,8 +118,8 @@ impl Menu {
                                                     66.);
                 quit_button.render(commands,
                                    asset_server,
-                                   camera.get_x(),
-                                   camera.get_y()-52.,
+                                   0.,
+                                   -52.,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 self.menu_elements = vec![continue_button, save_button, load_button, options_button, quit_button];
@@ -145,8 +145,8 @@ impl Menu {
                 if self.options_modified {
                     restart_to_apply_settings.render(commands,
                         asset_server,
-                        camera.get_x(),
-                        camera.get_y()+124.,
+                        0.,
+                        134.,
                         Z_VALUE_MENU_ELEMENTS);
                 }
 
@@ -157,8 +157,8 @@ impl Menu {
                                                  66.);
                 back_button.render(commands,
                                    asset_server,
-                                   camera.get_x(),
-                                   camera.get_y()+44.,
+                                   0.,
+                                   54.,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 let mut button_gfx = if options.four_k_mode { checked_box } else { unchecked_box };
@@ -172,8 +172,8 @@ impl Menu {
 
                 four_k_mode_button.render(commands,
                                    asset_server,
-                                   camera.get_x()+67.5,
-                                   camera.get_y()+17.5,
+                                   67.5,
+                                   27.5,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 button_gfx = if options.borderless { checked_box } else { unchecked_box };
@@ -187,8 +187,8 @@ impl Menu {
 
                 borderless_button.render(commands,
                                    asset_server,
-                                   camera.get_x()+67.5,
-                                   camera.get_y()-2.5,
+                                   67.5,
+                                   7.5,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 button_gfx = if options.vsync { checked_box } else { unchecked_box };
@@ -202,8 +202,8 @@ impl Menu {
 
                 vsync_button.render(commands,
                                    asset_server,
-                                   camera.get_x()+67.5,
-                                   camera.get_y()-22.5,
+                                   67.5,
+                                   -12.5,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 button_gfx = if options.fullscreen { checked_box } else { unchecked_box };
@@ -217,8 +217,23 @@ impl Menu {
 
                 fullscreen_button.render(commands,
                                    asset_server,
-                                   camera.get_x()+67.5,
-                                   camera.get_y()-42.5,
+                                   67.5,
+                                   -32.5,
+                                   Z_VALUE_MENU_ELEMENTS);
+
+                button_gfx = if options.hide_cursor_when_panning { checked_box } else { unchecked_box };
+                button_hover_gfx = if options.hide_cursor_when_panning { checked_box_hover } else { unchecked_box_hover };
+                let mut hide_cursor_button = UI::new(String::from("Hide cursor when panning"),
+                                                         button_gfx.to_string(),
+                                                    button_hover_gfx.to_string(),
+                                                       16.,
+                                                        17.);
+                hide_cursor_button.set_xoffset(-24.);
+
+                hide_cursor_button.render(commands,
+                                   asset_server,
+                                   67.5,
+                                   -52.5,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 self.menu_elements = vec![
@@ -226,7 +241,8 @@ impl Menu {
                     four_k_mode_button,
                     borderless_button,
                     vsync_button,
-                    fullscreen_button
+                    fullscreen_button,
+                    hide_cursor_button
                 ];
 
                 if self.options_modified {
@@ -246,8 +262,8 @@ impl Menu {
                                                      66.);
                 yes_quit_button.render(commands,
                                       asset_server,
-                                      camera.get_x(),
-                                      camera.get_y()-12.,
+                                      0.,
+                                      -12.,
                                       Z_VALUE_MENU_ELEMENTS);
 
                 let mut no_stay_button = UI::new(String::from("No, stay"),
@@ -257,8 +273,8 @@ impl Menu {
                                                     66.);
                 no_stay_button.render(commands,
                                    asset_server,
-                                   camera.get_x(),
-                                   camera.get_y()-36.,
+                                   0.,
+                                   -36.,
                                    Z_VALUE_MENU_ELEMENTS);
 
                 self.menu_elements = vec![yes_quit_button, no_stay_button];
@@ -269,7 +285,8 @@ impl Menu {
 
         self.entity = commands.spawn((
             Sprite::from_image(asset_server.load(&self.img)),
-            Transform::from_xyz(camera.get_x(), camera.get_y(), Z_VALUE_MENU),
+            Transform::from_xyz(0., 0., Z_VALUE_MENU),
+            RenderLayers::layer(UI_LAYER),
         )).insert(MenuEntity).id();
 
         self.initialized = true;
@@ -323,10 +340,20 @@ impl Menu {
         }
     }
 
+    /*
+     * Checks whether the mouse is over one of the buttons
+     *
+     * @param    f32    x position of the mouse in UI space
+     * @param    f32    y position of the mouse in UI space
+     * @returns  bool   true if a button would react to a click
+     */
+    pub fn is_hovering(&self, mouse_x: f32, mouse_y: f32) -> bool {
+        self.visible() && self.menu_elements.iter().any(|e| e.has_hover() && e.mouse_is_hovering(mouse_x, mouse_y))
+    }
+
     pub fn click_events(&mut self,
                         commands: &mut Commands,
                         asset_server: &Res<AssetServer>,
-                        cam: &ResMut<Camera>,
                         gamestate: &mut ResMut<Gamestate>,
                         mouse_x: f32,
                         mouse_y: f32) -> String {
@@ -351,18 +378,18 @@ impl Menu {
             },
             "Options" => {
                 self.set_mode(MODE_OPTIONS);
-                self.render(commands, asset_server, cam);
+                self.render(commands, asset_server);
             },
             "Quit" => {
                 self.set_mode(MODE_QUIT);
-                self.render(commands, asset_server, cam);
+                self.render(commands, asset_server);
             },
             "Yes, quit" => {
-                std::process::exit(0);
+                return String::from("quit");
             },
             "Back" | "No, stay" => {
                 self.reset_mode();
-                self.render(commands, asset_server, cam);
+                self.render(commands, asset_server);
             },
             "4K Mode" => {
                 return String::from("4k_mode");
@@ -376,6 +403,9 @@ impl Menu {
             "Fullscreen" => {
                 return String::from("fullscreen");
             },
+            "Hide cursor when panning" => {
+                return String::from("hide_cursor");
+            },
             _ => {
             }
         }
@@ -5,6 +5,7 @@ pub struct Options {
     pub borderless: bool,
     pub vsync: bool,
     pub fullscreen: bool,
+    pub hide_cursor_when_panning: bool,
 }
 
 const OPTIONS_JSON_PATH: &str = "options.json";
@@ -17,13 +18,14 @@ pub fn get_options() -> Options {
             "four_k_mode": false,
             "borderless": false,
             "vsync": true,
-            "fullscreen": false
+            "fullscreen": false,
+            "hide_cursor_when_panning": false
         }"#;
 
         let res = fs::write(OPTIONS_JSON_PATH, default_options);
         if let Err(e) = res { println!("{}", e) }
 
-        return Options { four_k_mode: false, borderless: false, vsync: true, fullscreen: false }
+        return Options { four_k_mode: false, borderless: false, vsync: true, fullscreen: false, hide_cursor_when_panning: false }
     }
 
     let contents = fs::read_to_string(OPTIONS_JSON_PATH).unwrap_or_default();
@@ -35,12 +37,14 @@ pub fn get_options() -> Options {
     let borderless = parsed["borderless"].as_bool().unwrap_or(false);
     let vsync = parsed["vsync"].as_bool().unwrap_or(false);
     let fullscreen = parsed["fullscreen"].as_bool().unwrap_or(false);
+    let hide_cursor_when_panning = parsed["hide_cursor_when_panning"].as_bool().unwrap_or(false);
 
     Options {
         four_k_mode,
         borderless,
         vsync,
         fullscreen,
+        hide_cursor_when_panning,
     }
 }
 
@@ -61,6 +65,9 @@ pub fn set_option(key: String, value: bool) {
         "Fullscreen" => {
             current_options.fullscreen = value;
         },
+        "Hide cursor when panning" => {
+            current_options.hide_cursor_when_panning = value;
+        },
         _ => {
         }
     }
@@ -70,12 +77,14 @@ pub fn set_option(key: String, value: bool) {
             "four_k_mode": {},
             "borderless": {},
             "vsync": {},
-            "fullscreen": {}
+            "fullscreen": {},
+            "hide_cursor_when_panning": {}
         }}"#,
         current_options.four_k_mode,
         current_options.borderless,
         current_options.vsync,
-        current_options.fullscreen
+        current_options.fullscreen,
+        current_options.hide_cursor_when_panning
     );
 
     let res = fs::write(OPTIONS_JSON_PATH, options_as_json);
@@ -99,6 +108,9 @@ pub fn toggle_option(key: String) {
         "Fullscreen" => {
             current_options.fullscreen
         },
+        "Hide cursor when panning" => {
+            current_options.hide_cursor_when_panning
+        },
         _ => {
             return;
         }
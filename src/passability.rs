@@ -0,0 +1,124 @@
+pub struct Passability {
+    width: u32,
+    height: u32,
+    walkable: Vec<bool>,
+    costs: Vec<u32>,
+    revision: u64,
+}
+
+impl Passability {
+
+    pub fn new(width: u32, height: u32) -> Passability {
+        let cells = (width * height) as usize;
+
+        Passability {
+            width,
+            height,
+            walkable: vec![true; cells],
+            costs: vec![1; cells],
+            revision: 0,
+        }
+    }
+
+    /*
+     * Converts a grid coordinate into an index of the cell vectors
+     *
+     * @param    u32            x coordinate
+     * @param    u32            y coordinate
+     *
+     * @returns  Option<usize>  index of the cell, or None if out of bounds
+     */
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        // tiles are generated column by column, so keep the same ordering
+        Some((x * self.height + y) as usize)
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn is_walkable(&self, x: u32, y: u32) -> bool {
+        match self.index(x, y) {
+            Some(i) => self.walkable[i],
+            None => false,
+        }
+    }
+
+    /*
+     * Whether a creature can enter the given cell
+     *
+     * @param    u32    x coordinate
+     * @param    u32    y coordinate
+     * @param    bool   whether the creature flies over blocking decals
+     *
+     * @returns  bool   true if the cell can be entered
+     */
+    pub fn is_passable(&self, x: u32, y: u32, flies: bool) -> bool {
+        match self.index(x, y) {
+            Some(i) => flies || self.walkable[i],
+            None => false,
+        }
+    }
+
+    /*
+     * Gets the number of turns a creature needs to enter the given cell
+     *
+     * @param    u32    x coordinate
+     * @param    u32    y coordinate
+     * @param    bool   whether the creature flies over the terrain
+     *
+     * @returns  u32    movement cost, or 0 if the cell is out of bounds
+     */
+    pub fn get_travel_cost(&self, x: u32, y: u32, flies: bool) -> u32 {
+        match flies {
+            true => self.get_cost(x, y).min(1),
+            false => self.get_cost(x, y),
+        }
+    }
+
+    /*
+     * Gets the number of turns needed to enter the given cell
+     *
+     * @param    u32   x coordinate
+     * @param    u32   y coordinate
+     *
+     * @returns  u32   movement cost, or 0 if the cell is out of bounds
+     */
+    pub fn get_cost(&self, x: u32, y: u32) -> u32 {
+        match self.index(x, y) {
+            Some(i) => self.costs[i],
+            None => 0,
+        }
+    }
+
+    /*
+     * Gets a counter that increases every time a cell is modified
+     */
+    pub fn get_revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn set_cell(&mut self, x: u32, y: u32, walkable: bool, cost: u32) {
+        let i = match self.index(x, y) {
+            Some(i) => i,
+            None => return,
+        };
+
+        let cost = cost.max(1);
+        if self.walkable[i] == walkable && self.costs[i] == cost {
+            return;
+        }
+
+        self.walkable[i] = walkable;
+        self.costs[i] = cost;
+        self.revision += 1;
+    }
+}
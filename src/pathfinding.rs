@@ -0,0 +1,244 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::constants::PATH_CACHE_SIZE;
+use crate::creature::{Creature, NEIGHBOURS};
+use crate::passability::Passability;
+use crate::utils::distance;
+
+type Path = Vec<(u32,u32)>;
+type Route = ((u32,u32),(u32,u32),bool);
+
+pub struct Pathfinder {
+    revision: u64,
+    cache: HashMap<Route, Option<Path>>,
+    // routes in the order they were cached, oldest first
+    order: VecDeque<Route>,
+}
+
+impl Pathfinder {
+
+    pub fn new() -> Pathfinder {
+        Pathfinder { revision: 0, cache: HashMap::new(), order: VecDeque::new() }
+    }
+
+    /*
+     * Finds the cheapest path between two cells of the level
+     *
+     * @param    Passability        walkability and terrain costs of the level
+     * @param    (u32,u32)          starting location
+     * @param    (u32,u32)          destination
+     * @param    (u32,u32)[]        cells currently occupied by other creatures
+     * @param    bool               whether blocking decals can be flown over
+     *
+     * @returns  Option<Path>       every step after the start up to and
+     *                              including the goal, or None if unreachable
+     */
+    pub fn find_path(&mut self,
+                     map: &Passability,
+                     start: (u32,u32),
+                     goal: (u32,u32),
+                     occupied: &[(u32,u32)],
+                     flies: bool) -> Option<Path> {
+
+        // any change to the map invalidates every cached path
+        if self.revision != map.get_revision() {
+            self.cache.clear();
+            self.order.clear();
+            self.revision = map.get_revision();
+        }
+
+        let route = (start, goal, flies);
+        let terrain_path = match self.cache.get(&route) {
+            Some(path) => path.clone(),
+            None => {
+                let path = Pathfinder::astar(map, start, goal, &[], flies);
+                self.remember(route, path.clone());
+                path
+            },
+        }?;
+
+        // creatures move every turn, so only the terrain-only path is cached
+        let blocked = terrain_path.iter()
+                                  .any(|step| *step != goal && occupied.contains(step));
+        if !blocked {
+            return Some(terrain_path);
+        }
+
+        Pathfinder::astar(map, start, goal, occupied, flies)
+    }
+
+    /*
+     * Caches a terrain-only path, forgetting the oldest one once full
+     *
+     * @param    Route          start, goal and whether the creature flies
+     * @param    Option<Path>   path found for the route
+     */
+    fn remember(&mut self, route: Route, path: Option<Path>) {
+        if self.cache.len() >= PATH_CACHE_SIZE {
+            if let Some(oldest) = self.order.pop_front() {
+                self.cache.remove(&oldest);
+            }
+        }
+
+        self.cache.insert(route, path);
+        self.order.push_back(route);
+    }
+
+    fn astar(map: &Passability, start: (u32,u32), goal: (u32,u32), occupied: &[(u32,u32)], flies: bool) -> Option<Path> {
+
+        if start == goal {
+            return Some(vec![]);
+        } else if !map.is_passable(goal.0, goal.1, flies) {
+            return None;
+        }
+
+        let width = map.get_width();
+        let height = map.get_height();
+        let index = |(x, y): (u32,u32)| (x * height + y) as usize;
+
+        let mut costs: Vec<u32> = vec![u32::MAX; (width * height) as usize];
+        let mut came_from: Vec<Option<(u32,u32)>> = vec![None; (width * height) as usize];
+        let mut open = BinaryHeap::new();
+
+        // diagonal steps cost the same as straight ones, so the distance is
+        // never more than the cheapest possible path
+        costs[index(start)] = 0;
+        open.push(Reverse((distance(start, goal), 0, start)));
+
+        while let Some(Reverse((_, cost, current))) = open.pop() {
+
+            if current == goal {
+                let mut path = vec![goal];
+                let mut step = goal;
+                while let Some(previous) = came_from[index(step)] {
+                    if previous == start {
+                        break;
+                    }
+                    path.push(previous);
+                    step = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            // skip stale entries of cells that were reached more cheaply
+            if cost > costs[index(current)] {
+                continue;
+            }
+
+            for direction in NEIGHBOURS {
+                let next = match Creature::get_neighbour(current.0, current.1, direction, width, height) {
+                    Some(n) => n,
+                    None => continue,
+                };
+
+                if !map.is_passable(next.0, next.1, flies) || (next != goal && occupied.contains(&next)) {
+                    continue;
+                }
+
+                let next_cost = cost + map.get_travel_cost(next.0, next.1, flies);
+                if next_cost >= costs[index(next)] {
+                    continue;
+                }
+
+                costs[index(next)] = next_cost;
+                came_from[index(next)] = Some(current);
+                open.push(Reverse((next_cost + distance(next, goal), next_cost, next)));
+            }
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a wall down the middle column with a single gap at the bottom
+    fn walled_map() -> Passability {
+        let mut map = Passability::new(5, 5);
+        for y in 0..4 {
+            map.set_cell(2, y, false, 1);
+        }
+        map
+    }
+
+    #[test]
+    fn finds_a_straight_path() {
+        let map = Passability::new(5, 5);
+        let path = Pathfinder::new().find_path(&map, (0,0), (3,0), &[], false);
+
+        assert_eq!(path, Some(vec![(1,0), (2,0), (3,0)]));
+    }
+
+    #[test]
+    fn cuts_corners_diagonally() {
+        let map = Passability::new(5, 5);
+        let path = Pathfinder::new().find_path(&map, (0,0), (3,3), &[], false);
+
+        assert_eq!(path, Some(vec![(1,1), (2,2), (3,3)]));
+    }
+
+    #[test]
+    fn walks_around_blocking_cells() {
+        let map = walled_map();
+        let path = Pathfinder::new().find_path(&map, (0,0), (4,0), &[], false).unwrap();
+
+        assert!(path.iter().all(|step| map.is_walkable(step.0, step.1)));
+        assert!(path.contains(&(2,4)));
+        assert_eq!(path.last(), Some(&(4,0)));
+    }
+
+    #[test]
+    fn flies_over_blocking_cells() {
+        let map = walled_map();
+        let path = Pathfinder::new().find_path(&map, (0,0), (4,0), &[], true);
+
+        assert_eq!(path.map(|p| p.len()), Some(4));
+    }
+
+    #[test]
+    fn gives_up_on_unreachable_goals() {
+        let mut map = walled_map();
+        map.set_cell(2, 4, false, 1);
+        let mut pathfinder = Pathfinder::new();
+
+        assert_eq!(pathfinder.find_path(&map, (0,0), (4,0), &[], false), None);
+        assert_eq!(pathfinder.find_path(&map, (0,0), (2,2), &[], false), None);
+    }
+
+    #[test]
+    fn forgets_paths_when_the_map_changes() {
+        let mut map = Passability::new(5, 5);
+        let mut pathfinder = Pathfinder::new();
+        pathfinder.find_path(&map, (0,0), (3,0), &[], false);
+
+        // setting a cell to what it already was keeps the cache
+        map.set_cell(4, 4, true, 1);
+        pathfinder.find_path(&map, (0,0), (4,4), &[], false);
+        assert_eq!(pathfinder.cache.len(), 2);
+
+        map.set_cell(1, 0, false, 1);
+        let path = pathfinder.find_path(&map, (0,0), (3,0), &[], false).unwrap();
+        assert!(!path.contains(&(1,0)));
+        assert_eq!(pathfinder.cache.len(), 1);
+    }
+
+    #[test]
+    fn keeps_the_cache_bounded() {
+        let map = Passability::new(30, 30);
+        let mut pathfinder = Pathfinder::new();
+        for x in 0..30 {
+            for y in 0..30 {
+                pathfinder.find_path(&map, (0,0), (x,y), &[], false);
+            }
+        }
+
+        assert_eq!(pathfinder.cache.len(), PATH_CACHE_SIZE);
+        assert_eq!(pathfinder.order.len(), PATH_CACHE_SIZE);
+        assert!(pathfinder.cache.contains_key(&((0,0), (29,29), false)));
+    }
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::Entity;
+
+pub struct Player {
+    x: u32,
+    y: u32,
+    pub img: String,
+    initialized: bool,
+    entity: Entity,
+}
+
+impl Player {
+    pub fn new(x: u32, y: u32, img: String) -> Player {
+        Player {
+            x,
+            y,
+            img,
+            initialized: false,
+            entity: Entity::from_raw(0),
+        }
+    }
+
+    pub fn get_entity(&mut self) -> Entity {
+        self.entity
+    }
+    pub fn set_entity(&mut self, entity: Entity) {
+        self.entity = entity;
+    }
+
+    pub fn get_initialized(&mut self) -> bool {
+        self.initialized
+    }
+    pub fn set_initialized(&mut self, initialized: bool) {
+        self.initialized = initialized;
+    }
+
+    pub fn get_x(&self) -> u32 {
+        self.x
+    }
+    pub fn get_y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn set_x(&mut self, x: u32) {
+        self.x = x;
+    }
+    pub fn set_y(&mut self, y: u32) {
+        self.y = y;
+    }
+}
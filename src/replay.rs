@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy::math::Vec2;
+use bevy::prelude::{
+    CursorLeft,
+    CursorMoved,
+    Entity,
+    EventReader,
+    EventWriter,
+    Events,
+    KeyCode,
+    MouseButton,
+    Query,
+    Real,
+    Res,
+    ResMut,
+    Resource,
+    Time,
+    With,
+};
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{PrimaryWindow, WindowEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::REPLAY_FLUSH_INTERVAL;
+
+#[derive(Serialize, Deserialize)]
+pub enum InputEvent {
+    Key(KeyCode, ButtonState),
+    Button(MouseButton, ButtonState),
+    Moved(Vec2),
+    Left,
+    Wheel(MouseScrollUnit, Vec2),
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Frame {
+    // how long the frame took in nanoseconds, plus the input that arrived during it
+    Input(u64, Vec<InputEvent>),
+    // a run of frames without any input, and how long they took altogether
+    Idle(u32, u64),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    seed: u64,
+    frames: Vec<Frame>,
+}
+
+#[derive(PartialEq)]
+enum Mode {
+    Off,
+    Recording,
+    Playback,
+}
+
+#[derive(Resource)]
+pub struct Recorder {
+    mode: Mode,
+    path: String,
+    replay: Replay,
+    frame: usize,
+    idle: u32,
+    since_flush: f32,
+}
+
+impl Replay {
+
+    /*
+     * Loads a replay file
+     *
+     * @param    string            path to the replay
+     *
+     * @returns  Option<Replay>    the replay, or None if it can't be read
+     */
+    pub fn load(path: &str) -> Option<Replay> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Recorder {
+
+    pub fn new() -> Recorder {
+        Recorder {
+            mode: Mode::Off,
+            path: "".to_string(),
+            replay: Replay { seed: 0, frames: vec![] },
+            frame: 0,
+            idle: 0,
+            since_flush: 0.,
+        }
+    }
+
+    /*
+     * Starts recording a session that was started with the given seed
+     *
+     * @param    string   path of the replay file to write
+     * @param    u64      seed of the random number generator
+     */
+    pub fn record(path: String, seed: u64) -> Recorder {
+        Recorder {
+            mode: Mode::Recording,
+            path,
+            replay: Replay { seed, frames: vec![] },
+            frame: 0,
+            idle: 0,
+            since_flush: 0.,
+        }
+    }
+
+    /*
+     * Plays back a previously recorded session
+     *
+     * @param    Replay   the recording
+     */
+    pub fn playback(replay: Replay) -> Recorder {
+        Recorder {
+            mode: Mode::Playback,
+            path: "".to_string(),
+            replay,
+            frame: 0,
+            idle: 0,
+            since_flush: 0.,
+        }
+    }
+
+    fn save(&self) {
+        let contents = match serde_json::to_string(&self.replay) {
+            Ok(contents) => contents,
+            Err(_) => return,
+        };
+
+        if std::fs::write(&self.path, contents).is_err() {
+            println!("Unable to write the replay file: {}", self.path);
+        }
+    }
+}
+
+// runs at the end of each frame, noting down what happened during it
+pub fn record_handler(mut recorder: ResMut<Recorder>,
+                      time: Res<Time<Real>>,
+                      mut window_events: EventReader<WindowEvent>,
+                      mut exits: EventReader<AppExit>) {
+
+    if recorder.mode != Mode::Recording {
+        return;
+    }
+
+    // every window event goes through a single queue, so it keeps the order the input arrived in
+    let events: Vec<InputEvent> = window_events.read().filter_map(|event| match event {
+        WindowEvent::KeyboardInput(event) => Some(InputEvent::Key(event.key_code, event.state)),
+        WindowEvent::MouseButtonInput(event) => Some(InputEvent::Button(event.button, event.state)),
+        WindowEvent::CursorMoved(event) => Some(InputEvent::Moved(event.position)),
+        WindowEvent::CursorLeft(_) => Some(InputEvent::Left),
+        WindowEvent::MouseWheel(event) => Some(InputEvent::Wheel(event.unit, Vec2::new(event.x, event.y))),
+        _ => None,
+    }).collect();
+
+    let nanos = time.delta().as_nanos() as u64;
+
+    match (events.is_empty(), recorder.replay.frames.last_mut()) {
+        (true, Some(Frame::Idle(count, total))) => {
+            *count += 1;
+            *total += nanos;
+        },
+        (true, _) => recorder.replay.frames.push(Frame::Idle(1, nanos)),
+        (false, _) => recorder.replay.frames.push(Frame::Input(nanos, events)),
+    }
+
+    recorder.since_flush += time.delta_secs();
+
+    // written out now and then as well, so a crash doesn't lose the whole session
+    if exits.read().next().is_some() || recorder.since_flush >= REPLAY_FLUSH_INTERVAL {
+        recorder.save();
+        recorder.since_flush = 0.;
+    }
+}
+
+// runs at the start of each frame, before the clock ticks and input is processed
+#[allow(clippy::too_many_arguments)]
+pub fn playback_handler(mut recorder: ResMut<Recorder>,
+                        mut strategy: ResMut<TimeUpdateStrategy>,
+                        mut keys: ResMut<Events<KeyboardInput>>,
+                        mut buttons: ResMut<Events<MouseButtonInput>>,
+                        mut moves: ResMut<Events<CursorMoved>>,
+                        mut leaves: ResMut<Events<CursorLeft>>,
+                        mut wheels: ResMut<Events<MouseWheel>>,
+                        mut exit: EventWriter<AppExit>,
+                        windows: Query<Entity, With<PrimaryWindow>>) {
+
+    if recorder.mode != Mode::Playback {
+        return;
+    }
+
+    // whatever the player is doing right now isn't part of the recording
+    keys.clear();
+    buttons.clear();
+    moves.clear();
+    leaves.clear();
+    wheels.clear();
+
+    let window = windows.get_single().unwrap_or(Entity::PLACEHOLDER);
+
+    let (nanos, events) = match recorder.replay.frames.get(recorder.frame) {
+        Some(Frame::Input(nanos, events)) => (*nanos, events),
+        // the run is spread evenly over its frames, the last one taking whatever is left over
+        Some(Frame::Idle(count, total)) => {
+            let (count, total) = (*count, *total);
+            let nanos = match recorder.idle + 1 == count {
+                true => total - total / count as u64 * recorder.idle as u64,
+                false => total / count as u64,
+            };
+            *strategy = TimeUpdateStrategy::ManualDuration(Duration::from_nanos(nanos));
+
+            recorder.idle += 1;
+            if recorder.idle >= count {
+                recorder.idle = 0;
+                recorder.frame += 1;
+            }
+            return;
+        },
+        None => {
+            println!("Replay finished.");
+            exit.send(AppExit::Success);
+            recorder.mode = Mode::Off;
+            return;
+        },
+    };
+
+    *strategy = TimeUpdateStrategy::ManualDuration(Duration::from_nanos(nanos));
+
+    for event in events.iter() {
+        match event {
+            InputEvent::Key(key_code, state) => {
+                keys.send(KeyboardInput {
+                    key_code: *key_code,
+                    logical_key: Key::Unidentified(NativeKey::Unidentified),
+                    state: *state,
+                    repeat: false,
+                    window,
+                });
+            },
+            InputEvent::Button(button, state) => {
+                buttons.send(MouseButtonInput {
+                    button: *button,
+                    state: *state,
+                    window,
+                });
+            },
+            InputEvent::Moved(position) => {
+                moves.send(CursorMoved {
+                    window,
+                    position: *position,
+                    delta: None,
+                });
+            },
+            InputEvent::Left => {
+                leaves.send(CursorLeft { window });
+            },
+            InputEvent::Wheel(unit, scroll) => {
+                wheels.send(MouseWheel {
+                    unit: *unit,
+                    x: scroll.x,
+                    y: scroll.y,
+                    window,
+                });
+            },
+        }
+    }
+
+    recorder.frame += 1;
+}
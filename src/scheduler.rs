@@ -0,0 +1,57 @@
+use bevy::prelude::{
+    Res,
+    ResMut,
+    Resource,
+};
+
+use crate::level::Level;
+
+// energy a creature needs to have built up before it is able to act
+pub const ACTION_COST: i32 = 4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Action {
+    Move((u32,u32)),
+    Wait,
+    Attack((u32,u32)),
+    Interact,
+}
+
+#[derive(Resource)]
+pub struct TurnCounter {
+    turn: u32,
+}
+
+impl TurnCounter {
+
+    pub fn new() -> TurnCounter {
+        TurnCounter { turn: 0 }
+    }
+
+    pub fn get_turn(&self) -> u32 {
+        self.turn
+    }
+}
+
+/*
+ * Sorts the actors that are ready into the order their actions resolve in
+ *
+ * @param    (usize,i32,u32)[]   index, energy and speed of each actor
+ *
+ * @returns  usize[]             indexes, the most energetic actor first,
+ *                               with ties going to the faster one and then
+ *                               to whichever was created first
+ */
+pub fn get_initiative_order(actors: &[(usize, i32, u32)]) -> Vec<usize> {
+    let mut order = actors.to_vec();
+    order.sort_by(|a, b| b.1.cmp(&a.1)
+                            .then(b.2.cmp(&a.2))
+                            .then(a.0.cmp(&b.0)));
+    order.iter().map(|(i, _, _)| *i).collect()
+}
+
+pub fn turn_counter_handler(lvl: Res<Level>, mut counter: ResMut<TurnCounter>) {
+    if counter.turn != lvl.get_turn() {
+        counter.turn = lvl.get_turn();
+    }
+}
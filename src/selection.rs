@@ -0,0 +1,354 @@
+use bevy::prelude::{
+    AssetServer,
+    BackgroundColor,
+    ButtonInput,
+    Color,
+    Commands,
+    Component,
+    DetectChanges,
+    Entity,
+    EventReader,
+    MouseButton,
+    Node,
+    PositionType,
+    Query,
+    Res,
+    ResMut,
+    Resource,
+    Sprite,
+    Text as BevyText,
+    TextColor,
+    TextFont,
+    Transform,
+    UiRect,
+    Val,
+    Vec2,
+    Visibility,
+    With,
+    Without,
+};
+
+use crate::camera::Camera;
+use crate::context::{ContextAction, ContextMenu};
+use crate::constants::{HUD_FONT_SIZE, SELECTION_DRAG_THRESHOLD, Z_VALUE_HIGHLIGHT, Z_VALUE_SELECTION};
+use crate::cursor::Cursor;
+use crate::ecosystem::get_display_name;
+use crate::gamestate::{Gamestate, Status};
+use crate::level::Level;
+use crate::tile::TILE_SIZE;
+
+pub fn register_context_items(context_menu: &mut ContextMenu) {
+    context_menu.register("inspect", "Inspect", |target| target.creature.is_some());
+}
+
+#[derive(Component)]
+pub struct SelectionRingEntity;
+
+#[derive(Component)]
+pub struct SelectionAreaEntity;
+
+#[derive(Component)]
+pub struct InspectorEntity;
+
+// both queries take &mut Visibility, so without this they would conflict (B0001)
+type AreaOnly = (With<SelectionAreaEntity>, Without<InspectorEntity>);
+
+#[derive(Resource)]
+pub struct Selection {
+    initialized: bool,
+    changed: bool,
+    selected: Vec<u32>,
+    rings: Vec<Entity>,
+    area: Entity,
+    inspector: Entity,
+    drag: Option<(Vec2,Vec2)>,
+}
+
+impl Selection {
+
+    pub fn new() -> Selection {
+        Selection {
+            initialized: false,
+            changed: false,
+            selected: vec![],
+            rings: vec![],
+            area: Entity::from_raw(0),
+            inspector: Entity::from_raw(0),
+            drag: None,
+        }
+    }
+
+    pub fn render(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>) {
+
+        if self.initialized {
+            commands.entity(self.area).despawn();
+            commands.entity(self.inspector).despawn();
+            self.initialized = false;
+        }
+
+        // a single pixel, stretched over the box being dragged out
+        self.area = commands.spawn((
+            Sprite::from_color(Color::srgba(1., 1., 1., 0.15), Vec2::ONE),
+            Transform::from_xyz(0., 0., Z_VALUE_HIGHLIGHT),
+            Visibility::Hidden,
+        )).insert(SelectionAreaEntity).id();
+
+        self.inspector = commands.spawn((
+            BevyText::new(""),
+            TextFont {
+                font: asset_server.load("fonts/eight_bit.ttf"),
+                font_size: HUD_FONT_SIZE,
+                ..Default::default()
+            },
+            TextColor(Color::WHITE),
+            BackgroundColor(Color::srgba(0., 0., 0., 0.7)),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.),
+                right: Val::Px(8.),
+                padding: UiRect::all(Val::Px(4.)),
+                ..Default::default()
+            },
+            Visibility::Hidden,
+        )).insert(InspectorEntity).id();
+
+        self.changed = true;
+        self.initialized = true;
+    }
+
+    /*
+     * Draws a ring under each selected creature, replacing the previous ones
+     *
+     * @param    Commands
+     * @param    AssetServer
+     * @param    Level          the current level
+     */
+    fn render_rings(&mut self, commands: &mut Commands, asset_server: &Res<AssetServer>, lvl: &Level) {
+
+        for entity in self.rings.drain(..) {
+            commands.entity(entity).despawn();
+        }
+
+        for id in self.selected.iter() {
+            let (x, y) = match lvl.get_creature_location(*id) {
+                Some(xy) => xy,
+                None => continue,
+            };
+
+            self.rings.push(commands.spawn((
+                Sprite::from_image(asset_server.load("img/ui/selection_ring.png")),
+                Transform::from_xyz(TILE_SIZE * x as f32, TILE_SIZE * y as f32, Z_VALUE_SELECTION),
+            )).insert(SelectionRingEntity).id());
+        }
+    }
+
+    pub fn get_selected(&self) -> &[u32] {
+        &self.selected
+    }
+
+    pub fn select(&mut self, ids: Vec<u32>) {
+        self.selected = ids;
+        self.changed = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.select(vec![]);
+    }
+
+    /*
+     * Finds the cells within a box dragged out over the level
+     *
+     * @param    (u32,u32)[]   cells to look through, such as the creature positions
+     * @param    Vec2          one corner of the box, in world space
+     * @param    Vec2          the opposite corner, in world space
+     *
+     * @returns  (u32,u32)[]   cells whose middle is within the box
+     */
+    pub fn get_cells_within(cells: &[(u32,u32)], start: Vec2, end: Vec2) -> Vec<(u32,u32)> {
+        let min = start.min(end);
+        let max = start.max(end);
+
+        cells.iter()
+             .filter(|(x, y)| {
+                 let position = Vec2::new(TILE_SIZE * *x as f32, TILE_SIZE * *y as f32);
+                 position.cmpge(min).all() && position.cmple(max).all()
+             })
+             .copied()
+             .collect()
+    }
+
+    /*
+     * Describes the selected creatures for the inspector panel
+     *
+     * @param    Level    the current level
+     *
+     * @returns  string   details of a single creature, or a line for each
+     *                    when several are selected
+     */
+    pub fn get_content(&self, lvl: &Level) -> String {
+        let creatures: Vec<_> = self.selected.iter()
+                                             .filter_map(|id| lvl.get_creature(*id))
+                                             .collect();
+
+        if let [creature] = creatures.as_slice() {
+            let species = lvl.get_species(creature.get_species());
+            let stats = species.stats;
+
+            return [
+                format!("{} #{}", get_display_name(creature.get_species()), creature.get_id()),
+                format!("Position {}, {}", creature.get_x(), creature.get_y()),
+                format!("HP {}/{}  Hunger {}/{}", creature.get_hp(), stats.hp, creature.get_hunger(), species.max_hunger),
+                format!("Attack {}  Defense {}", stats.attack, stats.defense),
+                format!("Speed {}  Sight {}", stats.speed, stats.sight),
+                format!("State: {}", creature.get_behaviour().get_name()),
+            ].join("\n");
+        }
+
+        let mut lines = vec![format!("{} selected", creatures.len())];
+        for creature in creatures {
+            lines.push(format!("{} #{}  {}",
+                               get_display_name(creature.get_species()),
+                               creature.get_id(),
+                               creature.get_behaviour().get_name()));
+        }
+
+        lines.join("\n")
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn selection_handler(mut commands: Commands,
+                         asset_server: Res<AssetServer>,
+                         gamestate: Res<Gamestate>,
+                         cursor: Res<Cursor>,
+                         cam: Res<Camera>,
+                         lvl: Res<Level>,
+                         buttons: Res<ButtonInput<MouseButton>>,
+                         context_menu: Res<ContextMenu>,
+                         mut actions: EventReader<ContextAction>,
+                         mut selection: ResMut<Selection>,
+                         mut areas: Query<(&mut Transform, &mut Visibility), AreaOnly>,
+                         mut inspectors: Query<(&mut BevyText, &mut Visibility), With<InspectorEntity>>) {
+
+    let playing = gamestate.get_status() == Status::Playing;
+
+    let pointer = match playing {
+        true => cursor.get_screen_position().map(|(x, y)| {
+            let (world_x, world_y) = cam.screen_to_world(x, y);
+            (Vec2::new(x, y), Vec2::new(world_x, world_y))
+        }),
+        false => None,
+    };
+
+    // a click whilst the context menu is open is meant for the menu
+    if buttons.just_pressed(MouseButton::Left) && !context_menu.is_open() {
+        selection.drag = pointer;
+    }
+
+    for event in actions.read() {
+        if let ("inspect", Some(id)) = (event.action, event.target.creature) {
+            selection.select(vec![id]);
+        }
+    }
+
+    // a short drag is still a click, picking whatever stands in the cell
+    if buttons.just_released(MouseButton::Left) {
+        if let (Some((start_screen, start_world)), Some((screen, world))) = (selection.drag.take(), pointer) {
+            let positions = lvl.get_creature_positions();
+            let cells = match start_screen.distance(screen) < SELECTION_DRAG_THRESHOLD {
+                true => lvl.get_cell_at(world.x, world.y)
+                           .filter(|cell| positions.contains(cell))
+                           .into_iter()
+                           .collect(),
+                false => Selection::get_cells_within(&positions, start_world, world),
+            };
+            let ids = cells.iter()
+                           .filter_map(|(x, y)| lvl.get_creature_at(*x, *y))
+                           .map(|c| c.get_id())
+                           .collect();
+            selection.select(ids);
+        }
+    }
+
+    if !playing {
+        selection.drag = None;
+    }
+
+    // creatures that have died can't stay selected
+    if lvl.is_changed() {
+        let before = selection.selected.len();
+        selection.selected.retain(|id| lvl.get_creature(*id).is_some());
+        if selection.selected.len() != before {
+            selection.changed = true;
+        }
+    }
+
+    if selection.changed || lvl.is_changed() {
+        selection.render_rings(&mut commands, &asset_server, &lvl);
+    }
+
+    for (mut transform, mut visibility) in areas.iter_mut() {
+        *visibility = match (selection.drag, pointer) {
+            (Some((start_screen, start_world)), Some((screen, world))) if start_screen.distance(screen) >= SELECTION_DRAG_THRESHOLD => {
+                let middle = (start_world + world) / 2.;
+                let size = (world - start_world).abs();
+                transform.translation.x = middle.x;
+                transform.translation.y = middle.y;
+                transform.scale.x = size.x;
+                transform.scale.y = size.y;
+                Visibility::Visible
+            },
+            _ => Visibility::Hidden,
+        };
+    }
+
+    for (mut text, mut visibility) in inspectors.iter_mut() {
+        if selection.changed || lvl.is_changed() {
+            text.0 = selection.get_content(&lvl);
+        }
+
+        *visibility = match playing && !selection.selected.is_empty() {
+            true => Visibility::Visible,
+            false => Visibility::Hidden,
+        };
+    }
+
+    selection.changed = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELLS: [(u32,u32); 4] = [(0,0), (2,3), (4,4), (10,1)];
+
+    fn world(x: f32, y: f32) -> Vec2 {
+        Vec2::new(TILE_SIZE * x, TILE_SIZE * y)
+    }
+
+    #[test]
+    fn box_around_some_cells() {
+        assert_eq!(Selection::get_cells_within(&CELLS, world(1., 2.), world(4.5, 4.5)), vec![(2,3), (4,4)]);
+    }
+
+    #[test]
+    fn box_dragged_in_reverse() {
+        let expected = vec![(2,3), (4,4)];
+
+        // from the top right to the bottom left, and from the bottom right to the top left
+        assert_eq!(Selection::get_cells_within(&CELLS, world(4.5, 4.5), world(1., 2.)), expected);
+        assert_eq!(Selection::get_cells_within(&CELLS, world(4.5, 2.), world(1., 4.5)), expected);
+        assert_eq!(Selection::get_cells_within(&CELLS, world(1., 4.5), world(4.5, 2.)), expected);
+    }
+
+    #[test]
+    fn box_edges_are_inclusive() {
+        assert_eq!(Selection::get_cells_within(&CELLS, world(2., 3.), world(4., 4.)), vec![(2,3), (4,4)]);
+    }
+
+    #[test]
+    fn box_without_cells() {
+        assert!(Selection::get_cells_within(&CELLS, world(5., 5.), world(9., 9.)).is_empty());
+        assert!(Selection::get_cells_within(&[], world(0., 0.), world(9., 9.)).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::{
+    AssetServer,
+    Commands,
+    DetectChanges,
+    Fixed,
+    Res,
+    ResMut,
+    Resource,
+    Time,
+};
+
+use crate::camera::Camera;
+use crate::gamestate::{Gamestate, Status};
+use crate::level::Level;
+
+// number of turns per second when running at 1x
+static TURNS_PER_SECOND: f64 = 2.0;
+
+// speed multipliers the real-time mode can cycle through
+static RATES: [f64; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+#[derive(Resource)]
+pub struct Simulation {
+    realtime: bool,
+    paused: bool,
+    rate: usize,
+}
+
+impl Simulation {
+
+    pub fn new() -> Simulation {
+        Simulation { realtime: false, paused: false, rate: 1 }
+    }
+
+    pub fn is_realtime(&self) -> bool {
+        self.realtime
+    }
+
+    pub fn toggle_realtime(&mut self) {
+        self.realtime = !self.realtime;
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /*
+     * Checks whether turns should currently be advancing on their own
+     *
+     * @returns  bool   true if in real-time mode and not paused
+     */
+    pub fn is_running(&self) -> bool {
+        self.realtime && !self.paused
+    }
+
+    pub fn get_rate(&self) -> f64 {
+        RATES[self.rate]
+    }
+
+    pub fn faster(&mut self) {
+        self.rate = (self.rate + 1).min(RATES.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.rate = self.rate.saturating_sub(1);
+    }
+
+    /*
+     * Describes the current mode for the HUD
+     *
+     * @returns  string   such as "Real-time 2x" or "Paused"
+     */
+    pub fn get_label(&self) -> String {
+        match (self.realtime, self.paused) {
+            (false, _) => "Turn-based".to_string(),
+            (true, true) => "Paused".to_string(),
+            (true, false) => format!("Real-time {}x", self.get_rate()),
+        }
+    }
+}
+
+// keeps the fixed timestep in line with the chosen rate
+pub fn simulation_rate_handler(sim: Res<Simulation>, mut time: ResMut<Time<Fixed>>) {
+    if !sim.is_changed() {
+        return;
+    }
+
+    time.set_timestep_seconds(1.0 / (TURNS_PER_SECOND * sim.get_rate()));
+}
+
+// runs on the fixed timestep, so the speed of the level doesn't depend on the frame rate
+pub fn simulation_handler(mut commands: Commands,
+                          asset_server: Res<AssetServer>,
+                          gamestate: Res<Gamestate>,
+                          sim: Res<Simulation>,
+                          mut cam: ResMut<Camera>,
+                          mut lvl: ResMut<Level>) {
+
+    if !sim.is_running() || gamestate.get_status() != Status::Playing {
+        return;
+    }
+
+    lvl.next_turn();
+    cam.shake_from(lvl.get_damage_events());
+    lvl.render_turn(&mut commands, &asset_server);
+}
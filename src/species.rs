@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::ecosystem::Diet;
+
+#[derive(Copy, Clone)]
+pub struct Stats {
+    pub hp: u32,
+    pub attack: u32,
+    pub defense: u32,
+    pub speed: u32,
+    pub sight: u32,
+}
+
+pub struct Species {
+    pub stats: Stats,
+    pub flies: bool,
+    pub pack: bool,
+    pub home_radius: u32,
+    pub idle_chance: u32,
+    pub hunts: Vec<String>,
+    pub fears: Vec<String>,
+    pub diet: Diet,
+    pub max_hunger: u32,
+    pub breed_chance: u32,
+    pub walk_sheet: Option<String>,
+    pub walk_frames: u32,
+}
+
+impl Species {
+
+    /*
+     * Loads the profile of every creature type from the species file
+     *
+     * @returns  HashMap<string,Species>   profiles keyed by creature type,
+     *                                     such as "wolf_"
+     */
+    pub fn load_all() -> HashMap<String, Species> {
+
+        let contents = include_str!("../assets/img/creatures/species.json");
+
+        let parsed: serde_json::Value = serde_json::from_str(contents).expect("Unable to open the species file.");
+
+        let mut species = HashMap::new();
+
+        let entries = match parsed.as_object() {
+            Some(entries) => entries,
+            None => return species,
+        };
+
+        for (name, entry) in entries.iter() {
+            let strings = |key: &str| -> Vec<String> {
+                entry[key].as_array()
+                          .map(|list| list.iter()
+                                          .filter_map(|s| s.as_str())
+                                          .map(|s| s.to_string())
+                                          .collect())
+                          .unwrap_or_default()
+            };
+
+            let stat = |key: &str, default: u64| -> u32 {
+                entry["stats"][key].as_u64().unwrap_or(default) as u32
+            };
+
+            // attempt to parse the values, but fall back to a plain walker
+            species.insert(name.clone(), Species {
+                stats: Stats {
+                    hp: stat("hp", 10).max(1),
+                    attack: stat("attack", 2),
+                    defense: stat("defense", 1),
+                    speed: stat("speed", 4).max(1),
+                    sight: stat("sight", 4),
+                },
+                flies: entry["flies"].as_bool().unwrap_or(false),
+                pack: entry["pack"].as_bool().unwrap_or(false),
+                home_radius: entry["home_radius"].as_u64().unwrap_or(6) as u32,
+                idle_chance: entry["idle_chance"].as_u64().unwrap_or(0) as u32,
+                hunts: strings("hunts"),
+                fears: strings("fears"),
+                diet: Diet::parse(entry["diet"].as_str().unwrap_or("none")),
+                max_hunger: entry["max_hunger"].as_u64().unwrap_or(0) as u32,
+                breed_chance: entry["breed_chance"].as_u64().unwrap_or(0) as u32,
+                walk_sheet: entry["walk_sheet"].as_str().map(|s| s.to_string()),
+                walk_frames: entry["walk_frames"].as_u64().unwrap_or(1).max(1) as u32,
+            });
+        }
+
+        species
+    }
+
+    pub fn get_default() -> Species {
+        Species {
+            stats: Stats {
+                hp: 10,
+                attack: 2,
+                defense: 1,
+                speed: 4,
+                sight: 4,
+            },
+            flies: false,
+            pack: false,
+            home_radius: 6,
+            idle_chance: 0,
+            hunts: vec![],
+            fears: vec![],
+            diet: Diet::None,
+            max_hunger: 0,
+            breed_chance: 0,
+            walk_sheet: None,
+            walk_frames: 1,
+        }
+    }
+}
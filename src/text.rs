@@ -1,15 +1,14 @@
 use bevy::{math::{Quat, Vec3}, prelude::{
     AssetServer, Color, Commands, Entity, Res, Text2d, TextFont, Transform
-}, text::TextColor};
+}, render::view::RenderLayers, text::TextColor};
 
 use crate::constants::{
     DIALOG_HOVER_COLOR,
     DIALOG_CHOICE_COLOR,
     DIALOG_CHOICE_HEIGHT,
     DIALOG_CHOICE_WIDTH,
-    MOUSE_GFX_HEIGHT,
-    MOUSE_GFX_WIDTH,
     TEXT_DIALOG_SCALE,
+    UI_LAYER,
 };
 
 pub struct Text {
@@ -60,7 +59,8 @@ impl Text {
                 rotation: Quat::from_rotation_z(0.),
                 scale: Vec3::new(TEXT_DIALOG_SCALE,TEXT_DIALOG_SCALE,TEXT_DIALOG_SCALE),
                 translation: Vec3::new(x, y, z),
-            }
+            },
+            RenderLayers::layer(UI_LAYER),
         )).id();
 
         self.initialized = true;
@@ -114,16 +114,8 @@ impl Text {
             return false;
         }
 
-        let x_offset = 24.;
-        let y_offset = 0.;
-
-        if (x + x_offset + MOUSE_GFX_WIDTH >= self.x)
-        && (x + x_offset + MOUSE_GFX_WIDTH <= self.x + DIALOG_CHOICE_WIDTH)
-        && (y + y_offset + MOUSE_GFX_HEIGHT >= self.y)
-        && (y + y_offset + MOUSE_GFX_HEIGHT <= self.y + DIALOG_CHOICE_HEIGHT) {
-            return true;
-        }
-
-        false
+        // text is drawn centered on its position
+        (x - self.x).abs() <= DIALOG_CHOICE_WIDTH / 2.
+        && (y - self.y).abs() <= DIALOG_CHOICE_HEIGHT / 2.
     }
 }
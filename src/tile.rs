@@ -21,6 +21,28 @@ impl Tile {
         }
     }
 
+    /*
+     * Number of turns needed to cross a tile, based on its image
+     *
+     * @param    string   tile image, such as "img/biomes/marsh/1.png"
+     *
+     * @returns  u32      movement cost of the tile
+     */
+    pub fn get_tile_movement_cost(img: &str) -> u32 {
+        match img {
+            // the boggiest patches of marsh and the deepest snow drifts
+            "img/biomes/marsh/1.png"  => 2,
+            "img/biomes/marsh/3.png"  => 2,
+            "img/biomes/marsh/7.png"  => 2,
+            "img/biomes/marsh/10.png" => 2,
+            "img/biomes/marsh/12.png" => 2,
+            "img/biomes/snow/1.png"   => 2,
+            "img/biomes/snow/2.png"   => 2,
+            "img/biomes/snow/4.png"   => 2,
+            _ => 1,
+        }
+    }
+
     pub fn get_entity(&mut self) -> Entity {
         self.entity
     }
@@ -7,8 +7,9 @@ use bevy::prelude::{
     Sprite,
     Transform,
 };
+use bevy::render::view::RenderLayers;
 
-use crate::constants::{MOUSE_GFX_HEIGHT,MOUSE_GFX_WIDTH};
+use crate::constants::UI_LAYER;
 
 #[derive(Component)]
 pub struct UIEntity;
@@ -42,6 +43,7 @@ impl UI {
             self.entity = commands.spawn((
                 Sprite::from_image(asset_server.load(&self.img)),
                 Transform::from_xyz(x+self.xoffset, y, z),
+                RenderLayers::layer(UI_LAYER),
             )).insert(UIEntity).id();
 
             self.entity_hover = commands.spawn(UIEntity)
@@ -70,6 +72,7 @@ impl UI {
         commands.entity(self.entity_hover).insert((
             Sprite::from_image(asset_server.load(&self.img_hover)),
             Transform::from_xyz(x+self.xoffset, y, z+0.01),
+            RenderLayers::layer(UI_LAYER),
         ));
 
         self.hovered = true;
@@ -108,21 +111,24 @@ impl UI {
         self.z
     }
 
+    pub fn has_hover(&self) -> bool {
+        !self.img_hover.is_empty()
+    }
+
     pub fn set_xoffset(&mut self, x: f32) {
         self.xoffset = x;
     }
 
+    /*
+     * Checks whether a point in UI space lies over the graphic, which is
+     * drawn centered on its position
+     *
+     * @param    f32    x position in UI space
+     * @param    f32    y position in UI space
+     * @returns  bool   true if the point is within the graphic
+     */
     pub fn mouse_is_hovering(&self, x: f32, y: f32) -> bool {
-        let mouse_gfx_height: f32 = MOUSE_GFX_HEIGHT;
-        let mouse_gfx_width: f32 = MOUSE_GFX_WIDTH;
-
-        if (x+mouse_gfx_width >= self.x)
-        && (x+mouse_gfx_width <= self.x + self.width)
-        && (y+mouse_gfx_height >= self.y)
-        && (y+mouse_gfx_height <= self.y + self.height) {
-            return true;
-        }
-
-        false
+        (x - (self.x + self.xoffset)).abs() <= self.width / 2.
+        && (y - self.y).abs() <= self.height / 2.
     }
 }
\ No newline at end of file
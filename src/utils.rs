@@ -1,3 +1,17 @@
+use std::sync::Mutex;
+
+// shared by every system, so that a seed reproduces the same level and turns
+static RNG: Mutex<Option<fastrand::Rng>> = Mutex::new(None);
+
+/*
+ * Seeds the random number generator
+ *
+ * @param    u64   seed
+ */
+pub fn seed(seed: u64) {
+    *RNG.lock().unwrap() = Some(fastrand::Rng::with_seed(seed));
+}
+
 /*
  * Generates a random number between the given range
  *
@@ -12,5 +26,17 @@ pub fn random(min: u32, max: u32) -> u32 {
     } else if min == max {
         return max;
     }
-    fastrand::u32(min..max)
+    RNG.lock().unwrap().get_or_insert_with(fastrand::Rng::new).u32(min..max)
+}
+
+/*
+ * Number of steps between two cells when moving in eight directions
+ *
+ * @param    (u32,u32)   first location
+ * @param    (u32,u32)   second location
+ *
+ * @returns  u32         Chebyshev distance between them
+ */
+pub fn distance(a: (u32,u32), b: (u32,u32)) -> u32 {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
 }